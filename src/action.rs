@@ -1,12 +1,21 @@
 use super::*;
 
 pub fn duplicate(
-    _: Trigger<triggers::Duplicate>,
+    trigger: Trigger<triggers::Duplicate>,
     mut notif: EventWriter<Notif>,
     mut commands: Commands,
     mut map: NonSendMut<Map>,
-    registry: Res<Registry>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
     consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    camera: Query<(
+        &bevy_mod_raycast::deferred::RaycastSource<()>,
+        &smooth_bevy_cameras::LookTransform,
+    )>,
     selected: Query<(Entity, &actor::Actor, &mut Transform), With<actor::Selected>>,
     mut cubes: Query<&mut Handle<wire::Wire>>,
 ) {
@@ -20,7 +29,7 @@ pub fn duplicate(
         });
         return;
     }
-    for (entity, actor, ..) in selected.iter() {
+    for (entity, ..) in selected.iter() {
         match cubes.get_mut(entity) {
             Ok(mut mat) => {
                 commands.entity(entity).remove::<actor::Selected>();
@@ -30,58 +39,148 @@ pub fn duplicate(
                 commands.entity(entity).remove::<actor::SelectedBundle>();
             }
         }
-        let len = map.asset_data.exports.len();
-        let insert = unreal_asset::types::PackageIndex::new(len as i32 + 1);
-        actor.duplicate(map, export_names);
-        let (path, new) = actor::Actor::new(map, insert).unwrap();
-        export_names[len] = new.name.clone();
+    }
+    let actors: Vec<_> = selected.iter().map(|(_, actor, _)| actor).collect();
+    let batch = stamp(&mut commands, &mut notif, map, export_names, &actors);
+    for actor in actors {
         notif.send(Notif {
             message: format!("{} duplicated", actor.name),
             kind: Warning,
         });
-        match path {
-            Some(ref path) => {
-                let (mesh, material) = &registry.meshes[path];
-                commands.spawn((
-                    actor::SelectedBundle::default(),
-                    MaterialMeshBundle {
-                        mesh: mesh.clone_weak(),
-                        material: material
-                            .as_ref()
-                            .map(|mat| registry.mats[mat].clone_weak())
-                            .unwrap_or(consts.grid.clone_weak()),
-                        transform: actor.transform(map),
-                        ..default()
-                    },
-                    bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
-                    new,
-                ));
-            }
-            None => {
-                commands
-                    .spawn((
-                        actor::Selected,
-                        MaterialMeshBundle {
-                            mesh: consts.cube.clone_weak(),
-                            material: consts.selected.clone_weak(),
-                            transform: actor.transform(map),
-                            ..default()
-                        },
-                        bevy::pbr::wireframe::NoWireframe,
-                        new,
-                    ))
-                    .with_children(|parent| {
-                        parent.spawn((
-                            consts.bounds.clone_weak(),
-                            SpatialBundle {
-                                visibility: Visibility::Hidden,
-                                ..default()
-                            },
-                            bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
-                        ));
-                    });
+    }
+    if let Some(target) = camera
+        .get_single()
+        .ok()
+        .filter(|_| trigger.event().0)
+        .and_then(|(source, look)| target(appdata.placement, source, look))
+    {
+        relocate(&mut commands, map, &batch, target)
+    }
+    dialog::spawn_new(
+        map,
+        batch,
+        &mut commands,
+        &mut registry,
+        &content,
+        &appdata,
+        &consts,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+}
+
+/// duplicates actors once recording it and returns the copies
+pub fn stamp(
+    commands: &mut Commands,
+    notif: &mut EventWriter<Notif>,
+    map: &mut Asset,
+    export_names: &mut Vec<String>,
+    actors: &[&actor::Actor],
+) -> Vec<(Vec<actor::Piece>, actor::Actor)> {
+    // duplicated together so references between them point at the copies
+    let copies = actor::duplicate_group(actors, map, export_names);
+    let mut batch = Vec::with_capacity(copies.len());
+    for (actor, len) in actors.iter().zip(copies) {
+        let insert = unreal_asset::types::PackageIndex::new(len as i32 + 1);
+        let (pieces, new) = match actor::Actor::new(map, insert) {
+            Ok(new) => new,
+            Err(e) => {
+                notif.send(Notif {
+                    message: format!("couldn't duplicate {}: {e}", actor.name),
+                    kind: Error,
+                });
+                continue;
             }
-        }
+        };
+        export_names[len] = new.name.clone();
+        commands.trigger(triggers::Record(patch::Op::Duplicate {
+            actor: patch::name(map, actor.export),
+            name: patch::name(map, len),
+        }));
+        batch.push((pieces, new));
+    }
+    batch
+}
+
+/// where copies go for a placement or none if they stay where they were
+pub fn target(
+    placement: Placement,
+    source: &bevy_mod_raycast::deferred::RaycastSource<()>,
+    look: &smooth_bevy_cameras::LookTransform,
+) -> Option<Vec3> {
+    let ahead = || look.eye + look.look_direction().unwrap_or_default() * 10.0;
+    match placement {
+        Placement::Original => None,
+        // in front of the camera when there's nothing under the cursor
+        Placement::Cursor => Some(
+            source
+                .get_nearest_intersection()
+                .map_or_else(ahead, |(_, data)| data.position()),
+        ),
+        Placement::Camera => Some(ahead()),
+    }
+}
+
+/// moves new actors so their centre is at the target keeping their layout
+pub fn relocate(
+    commands: &mut Commands,
+    map: &mut Asset,
+    batch: &[(Vec<actor::Piece>, actor::Actor)],
+    target: Vec3,
+) {
+    // attached actors follow their parent
+    let roots: Vec<_> = batch
+        .iter()
+        .map(|(_, actor)| actor)
+        .filter(|actor| actor.parent(map).is_none())
+        .collect();
+    if roots.is_empty() {
+        return;
+    }
+    let centre = roots
+        .iter()
+        .map(|actor| actor.transform(map).translation)
+        .sum::<Vec3>()
+        / roots.len() as f32;
+    for actor in roots {
+        actor.add_location(map, target - centre);
+        commands.trigger(triggers::Record(patch::transform(
+            map,
+            actor,
+            &actor.transform(map),
+        )));
+    }
+}
+
+/// moves, rotates and scales new actors together around a pivot
+pub fn arrange(
+    commands: &mut Commands,
+    map: &mut Asset,
+    batch: &[(Vec<actor::Piece>, actor::Actor)],
+    pivot: Vec3,
+    offset: Transform,
+) {
+    // attached actors follow their parent
+    let roots: Vec<_> = batch
+        .iter()
+        .map(|(_, actor)| actor)
+        .filter(|actor| actor.parent(map).is_none())
+        .collect();
+    for actor in roots {
+        let pos = actor.transform(map).translation;
+        actor.add_location(
+            map,
+            pivot + offset.rotation * (pos - pivot) + offset.translation - pos,
+        );
+        actor.combine_rotation(map, offset.rotation);
+        actor.mul_scale(map, offset.scale);
+        commands.trigger(triggers::Record(patch::transform(
+            map,
+            actor,
+            &actor.transform(map),
+        )));
     }
 }
 
@@ -90,7 +189,8 @@ pub fn delete(
     mut notif: EventWriter<Notif>,
     mut commands: Commands,
     mut map: NonSendMut<Map>,
-    selected: Query<(Entity, &actor::Actor, &mut Transform), With<actor::Selected>>,
+    mut deletion: ResMut<deletion::Deletion>,
+    selected: Query<(Entity, &actor::Actor), With<actor::Selected>>,
 ) {
     let Some((map, ..)) = &mut map.0 else { return };
     if selected.is_empty() {
@@ -100,14 +200,20 @@ pub fn delete(
         });
         return;
     }
-    for (entity, actor, ..) in selected.iter() {
-        actor.delete(map);
-        notif.send(Notif {
-            message: format!("{} deleted", actor.name),
-            kind: Warning,
-        });
-        commands.entity(entity).despawn_recursive()
+    let actors: Vec<_> = selected.iter().collect();
+    let refs: Vec<_> = actors.iter().map(|(_, actor)| *actor).collect();
+    let incoming = actor::incoming(map, &refs);
+    let children = actor::attached(map, &refs);
+    // only ask when something would be left dangling
+    if incoming.is_empty() && children.is_empty() {
+        deletion::remove(&mut commands, &mut notif, map, &actors);
+        return;
     }
+    deletion.ask(
+        actors.iter().map(|(entity, _)| *entity).collect(),
+        incoming,
+        children,
+    )
 }
 
 pub fn focus(
@@ -148,8 +254,8 @@ pub fn approach(
     }
 }
 
-pub fn copy(
-    _: Trigger<triggers::Copy>,
+pub fn copy_location(
+    _: Trigger<triggers::CopyLocation>,
     mut notif: EventWriter<Notif>,
     mut buffer: ResMut<Buffer>,
     selected: Query<(Entity, &actor::Actor, &mut Transform), With<actor::Selected>>,
@@ -171,8 +277,8 @@ pub fn copy(
     });
 }
 
-pub fn paste(
-    _: Trigger<triggers::Paste>,
+pub fn paste_location(
+    _: Trigger<triggers::PasteLocation>,
     mut notif: EventWriter<Notif>,
     mut map: NonSendMut<Map>,
     buffer: Res<Buffer>,
@@ -234,6 +340,51 @@ pub fn hide(_: Trigger<triggers::Hide>, mut hidden: ResMut<Hidden>) {
     hidden.0 = !hidden.0
 }
 
+pub fn retexture(
+    trigger: Trigger<triggers::Retexture>,
+    mut commands: Commands,
+    map: NonSend<Map>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    actors: Query<(&actor::Actor, &Children)>,
+    sections: Query<(), With<actor::Section>>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    let entity = trigger.event().0;
+    let Ok((actor, children)) = actors.get(entity) else {
+        return;
+    };
+    let mut pieces = blueprint::pieces(map, actor, &registry);
+    if appdata.textures {
+        let cache = config()
+            .filter(|_| appdata.cache)
+            .map(|path| path.join("cache"));
+        let mats: Vec<_> = pieces
+            .iter()
+            .flat_map(|piece| piece.materials.iter().flatten().cloned())
+            .collect();
+        dialog::load_materials(
+            mats,
+            &mut registry,
+            &content,
+            cache.as_deref(),
+            appdata.version(),
+            &mut materials,
+            &mut images,
+        );
+    }
+    for &child in children.iter().filter(|&&child| sections.contains(child)) {
+        commands.entity(child).despawn_recursive();
+    }
+    commands
+        .entity(entity)
+        .with_children(|parent| actor::sections(parent, &registry, &consts, &pieces));
+}
+
 #[test]
 fn aes() {
     let key = "0x620E8AD508F57F0E1A40BBE1929A490EDA59CA40FEFE4745D1D594F7F2C2E0CA";
@@ -8,12 +8,22 @@ use unreal_asset::{
     types::{fname::FName, PackageIndex, PackageIndexTrait},
 };
 
+mod convert;
 mod delete;
 mod duplicate;
+mod group;
+mod replace;
 mod transform;
 mod transplant;
 mod ui;
 
+pub use convert::version_name;
+pub use delete::{attached, incoming, level_script, retarget, unlink, Incoming};
+pub use group::{duplicate_group, group_of, transplant_group, Group};
+pub use transform::transform_of;
+pub use transplant::{on_extra_refs, on_import_refs, Report, Status};
+pub use ui::fname;
+
 pub const LOCATION: &str = "RelativeLocation";
 pub const ROTATION: &str = "RelativeRotation";
 pub const SCALE: &str = "RelativeScale3D";
@@ -46,6 +56,22 @@ pub struct Selected;
 #[derive(bevy::prelude::Component)]
 pub struct Matched;
 
+#[derive(bevy::prelude::Component)]
+pub struct Attached;
+
+/// a rendered material section of one of an actor's meshes
+#[derive(bevy::prelude::Component)]
+pub struct Section;
+
+/// a mesh component to render relative to its actor
+#[derive(Clone)]
+pub struct Piece {
+    pub mesh: String,
+    /// overrides per material slot where none falls back to the mesh's own
+    pub materials: Vec<Option<String>>,
+    pub transform: bevy::prelude::Transform,
+}
+
 #[derive(bevy::prelude::Component)]
 pub struct Actor {
     pub export: usize,
@@ -60,7 +86,7 @@ impl Actor {
         PackageIndex::new(self.export as i32 + 1)
     }
 
-    pub fn new(asset: &Asset, package: PackageIndex) -> Result<(Option<String>, Self), Error> {
+    pub fn new(asset: &Asset, package: PackageIndex) -> Result<(Vec<Piece>, Self), Error> {
         if package.index == 0 {
             return Err(Error::invalid_package_index(
                 "actor was null reference".to_string(),
@@ -98,74 +124,107 @@ impl Actor {
             .get_import(norm.base_export.class_index)
             .map(|import| import.object_name.get_owned_content())
             .unwrap_or_default();
-        let path = norm
-            .properties
-            .iter()
-            .find_map(|comp| {
-                cast!(Property, ObjectProperty, comp)
-                    .filter(|_| comp.get_name() == "StaticMeshComponent")
-            })
-            .and_then(|i| asset.get_export(i.value))
-            .and_then(Export::get_normal_export)
-            .and_then(|mesh| {
-                mesh.properties.iter().find_map(|mesh| {
-                    cast!(Property, ObjectProperty, mesh)
-                        .filter(|_| mesh.get_name() == "StaticMesh")
-                })
-            })
-            .and_then(|i| asset.get_import(i.value))
-            .and_then(|i| asset.get_import(i.outer_index))
-            .map(|path| path.object_name.get_owned_content());
         // normally these are further back so reversed should be a bit faster
+        let mut transform = None;
         for prop in norm.properties.iter().rev() {
             match prop.get_name().get_owned_content().as_str() {
                 // of course this wouldn't be able to be detected if all transforms were left default
                 LOCATION | ROTATION | SCALE => {
-                    return Ok((
-                        path,
-                        Self {
-                            export,
-                            transform: export,
-                            name,
-                            display,
-                            class,
-                        },
-                    ))
+                    transform = Some(export);
+                    break;
                 }
                 "RootComponent" => {
                     if let Property::ObjectProperty(obj) = prop {
                         if obj.value.is_export() {
-                            return Ok((
-                                path,
-                                Self {
-                                    export,
-                                    transform: obj.value.index as usize - 1,
-                                    name,
-                                    display,
-                                    class,
-                                },
-                            ));
+                            transform = Some(obj.value.index as usize - 1);
+                            break;
                         }
                     }
                 }
                 _ => continue,
             }
         }
-        norm.base_export.object_name.get_content(|name| {
-            Err(Error::no_data(format!(
-                "couldn't find transform component for {name}",
-            )))
-        })
+        let Some(transform) = transform else {
+            return norm.base_export.object_name.get_content(|name| {
+                Err(Error::no_data(format!(
+                    "couldn't find transform component for {name}",
+                )))
+            });
+        };
+        let actor = Self {
+            export,
+            transform,
+            name,
+            display,
+            class,
+        };
+        Ok((actor.meshes(asset), actor))
+    }
+
+    /// the export index of the actor's root component
+    pub fn root(&self) -> usize {
+        self.transform
+    }
+
+    /// gets the export indexes of all the actor's components
+    pub fn components(&self, asset: &Asset) -> Vec<usize> {
+        asset.asset_data.exports[self.export]
+            .get_base_export()
+            .create_before_serialization_dependencies
+            .iter()
+            .filter(|dep| dep.is_export())
+            .map(|dep| dep.index as usize - 1)
+            .filter(|&i| i != self.export && i < asset.asset_data.exports.len())
+            .collect()
+    }
+
+    /// gets the mesh and materials of every mesh component along with its transform relative to the actor
+    pub fn meshes(&self, asset: &Asset) -> Vec<Piece> {
+        let mut components = self.components(asset);
+        if !components.contains(&self.transform) {
+            components.insert(0, self.transform)
+        }
+        let root = transform::world_of(asset, self.transform)
+            .compute_matrix()
+            .inverse();
+        components
+            .into_iter()
+            .filter_map(|export| {
+                let mesh = mesh_of(asset, export)?;
+                // attachments can chain through other components so go through world space
+                let transform = match export == self.transform {
+                    true => bevy::prelude::Transform::IDENTITY,
+                    false => bevy::prelude::Transform::from_matrix(
+                        root * transform::world_of(asset, export).compute_matrix(),
+                    ),
+                };
+                Some(Piece {
+                    mesh,
+                    materials: materials_of(asset, export),
+                    transform,
+                })
+            })
+            .collect()
+    }
+
+    /// gets the material overrides of every mesh component to tell when they change
+    pub fn materials(&self, asset: &Asset) -> Vec<Vec<Option<String>>> {
+        let mut components = self.components(asset);
+        components.push(self.transform);
+        components
+            .into_iter()
+            .map(|export| materials_of(asset, export))
+            .collect()
     }
 
-    /// gets all exports related to the given actor
-    fn get_actor_exports(&self, asset: &super::Asset, offset: usize) -> Vec<super::Export> {
+    /// the indices of the actor's exports starting with the actor
+    pub fn export_indices(&self, asset: &super::Asset) -> Vec<PackageIndex> {
+        // without a level there are no other actors to leave out
         let level = asset
             .asset_data
             .exports
             .iter()
-            .find_map(|ex| unreal_asset::cast!(Export, LevelExport, ex))
-            .unwrap();
+            .find_map(|ex| unreal_asset::cast!(Export, LevelExport, ex));
         // get references to all the actor's children
         let mut child_indexes: Vec<PackageIndex> = asset.asset_data.exports[self.export]
             .get_base_export()
@@ -179,37 +238,214 @@ impl Actor {
             .iter()
             .enumerate()
             .rev()
-            .filter_map(|(i, child)| level.actors.contains(child).then_some(i))
+            .filter_map(|(i, child)| {
+                level
+                    .is_some_and(|level| level.actors.contains(child))
+                    .then_some(i)
+            })
             .collect();
         for i in actors {
             child_indexes.remove(i);
         }
         // add the top-level actor reference
         child_indexes.insert(0, self.index());
+        child_indexes.retain(|index| asset.get_export(*index).is_some());
+        child_indexes
+    }
 
-        // get all the exports from those indexes
-        let mut children: Vec<_> = child_indexes
+    pub fn get_actor_exports(&self, asset: &super::Asset, offset: usize) -> Vec<super::Export> {
+        self.get_group_exports(asset, offset, &Group::new())
+    }
+
+    /// gets the actor's exports with references to other actors in a group pointed at their copies
+    pub fn get_group_exports(
+        &self,
+        asset: &super::Asset,
+        offset: usize,
+        group: &Group,
+    ) -> Vec<super::Export> {
+        let mut children = self.exports(asset);
+        self.remap(asset, &mut children, offset, group);
+        children
+    }
+
+    /// the actor's exports as they are in the package
+    pub fn exports(&self, asset: &super::Asset) -> Vec<super::Export> {
+        self.export_indices(asset)
             .iter()
             .filter_map(|index| asset.get_export(*index))
             // i'm pretty sure i have to clone here so i can modify then insert data
             .cloned()
-            .collect();
+            .collect()
+    }
 
+    /// updates references in the actor's exports to what they will be once added after offset
+    pub fn remap(
+        &self,
+        asset: &super::Asset,
+        children: &mut [super::Export],
+        offset: usize,
+        group: &Group,
+    ) {
         let package_offset = (offset + 1) as i32;
-        // update export references to what they will be once added
-        for (i, child_index) in child_indexes.into_iter().enumerate() {
-            for child in children.iter_mut() {
-                on_export_refs(child, |index| {
-                    if index == &child_index {
-                        index.index = package_offset + i as i32;
-                    }
-                });
-            }
+        let mut remap = group.clone();
+        for (i, child_index) in self.export_indices(asset).into_iter().enumerate() {
+            remap.insert(child_index.index, package_offset + i as i32);
+        }
+        for child in children.iter_mut() {
+            on_export_refs(child, |index| {
+                if let Some(new) = remap.get(&index.index) {
+                    index.index = *new
+                }
+            });
+        }
+    }
+}
+
+/// gets the package path of a component's static mesh
+pub fn mesh_of(asset: &Asset, export: usize) -> Option<String> {
+    asset.asset_data.exports[export]
+        .get_normal_export()?
+        .properties
+        .iter()
+        .find_map(|prop| {
+            cast!(Property, ObjectProperty, prop).filter(|_| prop.get_name() == "StaticMesh")
+        })
+        .and_then(|obj| asset.get_import(obj.value))
+        .and_then(|mesh| asset.get_import(mesh.outer_index))
+        .map(|path| path.object_name.get_owned_content())
+}
+
+/// gets the package paths of a component's override materials per slot
+pub fn materials_of(asset: &Asset, export: usize) -> Vec<Option<String>> {
+    match asset.asset_data.exports[export]
+        .get_normal_export()
+        .and_then(|norm| {
+            norm.properties
+                .iter()
+                .find(|prop| prop.get_name() == "OverrideMaterials")
+        }) {
+        Some(Property::ArrayProperty(arr)) => arr
+            .value
+            .iter()
+            .map(|slot| {
+                cast!(Property, ObjectProperty, slot)
+                    .and_then(|obj| asset.get_import(obj.value))
+                    .and_then(|mat| asset.get_import(mat.outer_index))
+                    .map(|path| path.object_name.get_owned_content())
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// the override or mesh material in a slot falling back to the grid
+pub fn material(
+    registry: &super::Registry,
+    consts: &super::Constants,
+    piece: &Piece,
+    defaults: &[String],
+    slot: usize,
+) -> bevy::prelude::Handle<super::unlit::Unlit> {
+    use bevy::prelude::*;
+    piece
+        .materials
+        .get(slot)
+        .cloned()
+        .flatten()
+        .or_else(|| defaults.get(slot).cloned())
+        .and_then(|mat| registry.mats.get(&mat)?.as_ref().map(Handle::clone_weak))
+        .unwrap_or(consts.grid.clone_weak())
+}
+
+/// spawns a child for each material section of the given meshes
+pub fn sections(
+    parent: &mut bevy::prelude::ChildBuilder,
+    registry: &super::Registry,
+    consts: &super::Constants,
+    pieces: &[Piece],
+) {
+    use bevy::prelude::*;
+    for piece in pieces {
+        let Some((sections, defaults)) = registry.meshes.get(&piece.mesh) else {
+            continue;
+        };
+        for (mesh, slot) in sections {
+            let material = material(registry, consts, piece, defaults, *slot);
+            parent.spawn((
+                MaterialMeshBundle {
+                    mesh: mesh.clone_weak(),
+                    material,
+                    transform: piece.transform,
+                    ..default()
+                },
+                Section,
+                bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
+                // so the whole actor is outlined when selected
+                bevy_mod_outline::InheritOutlineBundle::default(),
+            ));
         }
-        children
     }
 }
 
+/// spawns an actor with a child for each of its meshes or as a wire cube if it has none
+pub fn spawn(
+    commands: &mut bevy::prelude::Commands,
+    registry: &super::Registry,
+    consts: &super::Constants,
+    transform: bevy::prelude::Transform,
+    pieces: Vec<Piece>,
+    actor: Actor,
+    selected: bool,
+) -> bevy::prelude::Entity {
+    use bevy::prelude::*;
+    // meshes which failed to load are left out
+    if !pieces
+        .iter()
+        .any(|piece| registry.meshes.contains_key(&piece.mesh))
+    {
+        let mut entity = commands.spawn((
+            MaterialMeshBundle {
+                mesh: consts.cube.clone_weak(),
+                material: match selected {
+                    true => consts.selected.clone_weak(),
+                    false => consts.unselected.clone_weak(),
+                },
+                transform,
+                ..default()
+            },
+            bevy::pbr::wireframe::NoWireframe,
+            actor,
+        ));
+        entity.with_children(|parent| {
+            parent.spawn((
+                consts.bounds.clone_weak(),
+                SpatialBundle {
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
+            ));
+        });
+        if selected {
+            entity.insert(Selected);
+        }
+        return entity.id();
+    }
+    let mut entity = commands.spawn((
+        SpatialBundle {
+            transform,
+            ..default()
+        },
+        actor,
+    ));
+    entity.with_children(|parent| sections(parent, registry, consts, &pieces));
+    if selected {
+        entity.insert(SelectedBundle::default());
+    }
+    entity.id()
+}
+
 /// gets all actor exports within a map (all exports direct children of PersistentLevel)
 pub fn get_actors(asset: &Asset) -> Vec<PackageIndex> {
     match asset
@@ -228,6 +464,14 @@ pub fn get_actors(asset: &Asset) -> Vec<PackageIndex> {
     }
 }
 
+/// the name as the engine shows it which tells apart names differing only by number
+pub fn full_name(name: &FName) -> String {
+    match name.get_number() {
+        0 => name.get_owned_content(),
+        number => format!("{}_{}", name.get_owned_content(), number - 1),
+    }
+}
+
 /// creates and assigns a unique name
 fn give_unique_name(orig: &mut FName, asset: &mut Asset) {
     // for the cases where the number is unnecessary
@@ -252,7 +496,7 @@ fn give_unique_name(orig: &mut FName, asset: &mut Asset) {
 }
 
 /// on all possible export references
-fn on_export_refs(export: &mut super::Export, mut func: impl FnMut(&mut PackageIndex)) {
+pub fn on_export_refs(export: &mut super::Export, mut func: impl FnMut(&mut PackageIndex)) {
     if let Some(norm) = export.get_normal_export_mut() {
         for prop in norm.properties.iter_mut() {
             on_prop_refs(prop, &mut func);
@@ -304,7 +548,7 @@ fn on_props(prop: &mut Property, func: &mut impl FnMut(&mut Property)) {
 }
 
 /// on any possible references stashed away in properties
-fn on_prop_refs(prop: &mut Property, func: &mut impl FnMut(&mut PackageIndex)) {
+pub fn on_prop_refs(prop: &mut Property, func: &mut impl FnMut(&mut PackageIndex)) {
     on_props(prop, &mut |prop| match prop {
         Property::ObjectProperty(obj) => {
             func(&mut obj.value);
@@ -0,0 +1,150 @@
+use super::*;
+use unreal_asset::{
+    engine_version::EngineVersion, object_version::ObjectVersionUE5, types::fname::ToSerializedName,
+};
+
+// properties are read into the same shape whatever the version and written for the recipient
+// so large world coordinate vectors become floats or doubles on their own
+// what's left is the data after the properties which has to be laid out by hand
+// and soft references whose names are split differently from 5.1 which are refused
+
+/// the name of a version for reports
+pub fn version_name(version: EngineVersion) -> &'static str {
+    crate::VERSIONS
+        .iter()
+        .find(|(ver, _)| *ver == version)
+        .map_or("unknown", |(_, name)| name)
+}
+
+/// whether two packages were written by different engine or object versions
+pub fn mismatch(recipient: &Asset, donor: &Asset) -> bool {
+    recipient.get_engine_version() != donor.get_engine_version()
+        || recipient.get_object_version() != donor.get_object_version()
+        || recipient.get_object_version_ue5() != donor.get_object_version_ue5()
+        || recipient.has_unversioned_properties() != donor.has_unversioned_properties()
+}
+
+/// converts an actor's exports to the recipient's layout or lists why they can't be
+pub fn convert(
+    children: &mut [crate::Export],
+    recipient: &Asset,
+    donor: &Asset,
+) -> Result<(), Vec<String>> {
+    let from = donor.get_engine_version() >= EngineVersion::VER_UE5_1;
+    let to = recipient.get_engine_version() >= EngineVersion::VER_UE5_1;
+    let doubles = recipient.get_object_version_ue5() >= ObjectVersionUE5::LARGE_WORLD_COORDINATES;
+    let target = version_name(recipient.get_engine_version());
+    // unversioned properties are laid out by the game's mappings which aren't known here
+    if recipient.has_unversioned_properties() != donor.has_unversioned_properties() {
+        return Err(vec![
+            "only one of the maps has unversioned properties so they can't be rewritten".into(),
+        ]);
+    }
+    let mut problems = Vec::new();
+    for (i, child) in children.iter_mut().enumerate() {
+        let name = child.get_base_export().object_name.get_owned_content();
+        // brushes serialise their geometry natively
+        if name == "Model" {
+            problems.push(format!("{name} is brush geometry which can't be converted"));
+            continue;
+        }
+        let Some(norm) = child.get_normal_export_mut() else {
+            problems.push(format!("{name} couldn't be read so can't be converted"));
+            continue;
+        };
+        for prop in norm.properties.iter_mut() {
+            on_props(prop, &mut |prop| {
+                // soft references went from one name to a package and asset name in 5.1
+                // and which is which can't be told from a path alone
+                if from != to && prop.to_serialized_name().starts_with("Soft") {
+                    problems.push(format!(
+                        "{name}.{} is a soft reference whose names {target} lays out differently",
+                        prop.get_name().get_owned_content()
+                    ));
+                    return;
+                }
+                let value = match prop {
+                    Property::VectorProperty(vec) if !doubles => &vec.value,
+                    Property::RotatorProperty(rot) if !doubles => &rot.value,
+                    _ => return,
+                };
+                if [value.x.0, value.y.0, value.z.0]
+                    .iter()
+                    .any(|val| val.abs() > f32::MAX as f64)
+                {
+                    problems.push(format!(
+                        "{name}.{} is too large for {target} which uses floats",
+                        prop.get_name().get_owned_content()
+                    ))
+                }
+            })
+        }
+        // the actor itself doesn't have the actor label block
+        if i == 0 || from == to {
+            continue;
+        }
+        if let Err(problem) = label(&mut norm.extras, to) {
+            problems.push(format!("{name} {problem} {target}"))
+        }
+    }
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(problems),
+    }
+}
+
+/// adds or removes the actor label block after a component's guid flag
+fn label(extras: &mut Vec<u8>, to: bool) -> Result<(), &'static str> {
+    match to {
+        // an empty block after the guid flag
+        true if extras.len() >= 4 => {
+            extras.splice(4..4, [0; 4]);
+        }
+        false if extras.len() >= 8 && extras[4..8] == [0; 4] => {
+            extras.drain(4..8);
+        }
+        // the referenced objects would be lost
+        false if extras.len() >= 8 => {
+            return Err("has references in its actor label block which isn't in")
+        }
+        _ => return Err("has data after its properties in a layout which can't be converted for"),
+    }
+    Ok(())
+}
+
+#[test]
+fn labels() {
+    // a guid flag then the rest of the component
+    let before = vec![0, 0, 0, 0, 7, 7];
+    let mut extras = before.clone();
+    label(&mut extras, true).unwrap();
+    assert_eq!(extras, [0, 0, 0, 0, 0, 0, 0, 0, 7, 7]);
+    label(&mut extras, false).unwrap();
+    assert_eq!(extras, before);
+    // a label block which references something
+    let mut extras = vec![0, 0, 0, 0, 1, 0, 0, 0, 7, 7];
+    assert!(label(&mut extras, false).is_err());
+    assert_eq!(extras.len(), 10);
+    assert!(label(&mut vec![0; 2], true).is_err());
+}
+
+#[test]
+fn refusals() {
+    let mut map = crate::regression::sample();
+    let actor = crate::regression::stand_ins(&mut map).remove(0);
+    assert_eq!(version_name(map.get_engine_version()), "4.27");
+    assert!(!mismatch(&map, &map));
+    let mut children = actor.exports(&map);
+    assert!(convert(&mut children, &map, &map).is_ok());
+    // a location only a double can hold
+    let mut names = map.get_name_map();
+    let norm = children[0].get_normal_export_mut().unwrap();
+    crate::props::set_vector(
+        &mut norm.properties,
+        LOCATION,
+        bevy::math::DVec3::splat(f64::MAX),
+        &mut names,
+    );
+    let problems = convert(&mut children, &map, &map).unwrap_err();
+    assert!(problems.len() == 1 && problems[0].contains("too large"));
+}
@@ -27,3 +27,258 @@ impl Actor {
         }
     }
 }
+
+/// a reference to an actor being deleted from somewhere outside of it
+pub struct Incoming {
+    /// the export the reference is stored in
+    pub from: usize,
+    /// the actor it points at
+    pub to: usize,
+    pub via: String,
+    /// whether the level blueprint holds it
+    pub script: bool,
+}
+
+/// each of the actors' exports mapped to the actor it belongs to
+fn owned(map: &Asset, actors: &[&Actor]) -> std::collections::HashMap<i32, usize> {
+    actors
+        .iter()
+        .flat_map(|actor| {
+            actor
+                .export_indices(map)
+                .into_iter()
+                .map(|index| (index.index, actor.export))
+        })
+        .collect()
+}
+
+/// the level blueprint's actor which is the one whose class is compiled into the map
+pub fn level_script(map: &Asset) -> Option<usize> {
+    get_actors(map)
+        .into_iter()
+        .find(|index| {
+            map.get_export(*index)
+                .is_some_and(|ex| ex.get_base_export().class_index.is_export())
+        })
+        .map(|index| index.index as usize - 1)
+}
+
+/// whether an export is part of the level blueprint
+fn scripted(map: &Asset, script: usize, export: usize) -> bool {
+    let class = map.asset_data.exports[script].get_base_export().class_index;
+    let mut index = PackageIndex::new(export as i32 + 1);
+    // outers can't really loop but guard against broken maps
+    for _ in 0..64 {
+        if index.index == script as i32 + 1 || index == class {
+            return true;
+        }
+        match map.get_export(index) {
+            Some(ex) if index.is_export() => index = ex.get_base_export().outer_index,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// finds object and delegate references to the actors from outside of them
+pub fn incoming(map: &Asset, actors: &[&Actor]) -> Vec<Incoming> {
+    let owned = owned(map, actors);
+    let script = level_script(map);
+    let mut found = Vec::new();
+    for (from, ex) in map.asset_data.exports.iter().enumerate() {
+        // the level's actor list is handled by delete
+        if owned.contains_key(&(from as i32 + 1)) || cast!(Export, LevelExport, ex).is_some() {
+            continue;
+        }
+        let Some(norm) = ex.get_normal_export() else {
+            continue;
+        };
+        let script = script.is_some_and(|script| scripted(map, script, from));
+        // the ref functions need mutable access so work on copies
+        for mut prop in norm.properties.iter().cloned() {
+            let via = prop.get_name().get_owned_content();
+            on_prop_refs(&mut prop, &mut |index| {
+                if let Some(to) = owned.get(&index.index) {
+                    found.push(Incoming {
+                        from,
+                        to: *to,
+                        via: via.clone(),
+                        script,
+                    })
+                }
+            });
+        }
+    }
+    // events on the actors bound to functions in the level blueprint are lost with them
+    if let Some(script) = script {
+        let script = script as i32 + 1;
+        for (index, to) in owned.iter() {
+            let from = *index as usize - 1;
+            let Some(norm) = map.asset_data.exports[from].get_normal_export() else {
+                continue;
+            };
+            for mut prop in norm.properties.iter().cloned() {
+                let via = prop.get_name().get_owned_content();
+                let mut bound = false;
+                on_prop_refs(&mut prop, &mut |index| bound |= index.index == script);
+                if bound {
+                    found.push(Incoming {
+                        from,
+                        to: *to,
+                        via: format!("{via} binding"),
+                        script: true,
+                    })
+                }
+            }
+        }
+    }
+    found
+}
+
+/// nulls references to the actors from outside of them so nothing dangles once they're gone
+pub fn unlink(map: &mut Asset, actors: &[&Actor]) {
+    let owned = owned(map, actors);
+    for (i, ex) in map.asset_data.exports.iter_mut().enumerate() {
+        if owned.contains_key(&(i as i32 + 1)) || cast!(Export, LevelExport, ex).is_some() {
+            continue;
+        }
+        if let Some(norm) = ex.get_normal_export_mut() {
+            for prop in norm.properties.iter_mut() {
+                on_prop_refs(prop, &mut |index| {
+                    if owned.contains_key(&index.index) {
+                        index.index = 0
+                    }
+                });
+            }
+        }
+        // dependencies are dropped rather than nulled
+        let base = ex.get_base_export_mut();
+        for deps in [
+            &mut base.serialization_before_serialization_dependencies,
+            &mut base.create_before_serialization_dependencies,
+            &mut base.serialization_before_create_dependencies,
+            &mut base.create_before_create_dependencies,
+        ] {
+            deps.retain(|dep| !owned.contains_key(&dep.index))
+        }
+    }
+}
+
+/// points references to an actor from outside of it at a replacement instead
+/// matching components by name and leaving the rest to be nulled by unlink
+pub fn retarget(map: &mut Asset, from: &Actor, to: &Actor) {
+    let name = |map: &Asset, export: usize| {
+        full_name(&map.asset_data.exports[export].get_base_export().object_name)
+    };
+    let index = |export: usize| export as i32 + 1;
+    let mut moved = std::collections::HashMap::from([
+        (index(from.export), index(to.export)),
+        (index(from.root()), index(to.root())),
+    ]);
+    let theirs = to.components(map);
+    for component in from.components(map) {
+        let mine = name(map, component);
+        if let Some(other) = theirs.iter().find(|other| name(map, **other) == mine) {
+            moved.entry(index(component)).or_insert(index(*other));
+        }
+    }
+    // the replacement's own references are left alone too
+    let owned = owned(map, &[from, to]);
+    for (i, ex) in map.asset_data.exports.iter_mut().enumerate() {
+        if owned.contains_key(&index(i)) || cast!(Export, LevelExport, ex).is_some() {
+            continue;
+        }
+        let mut retarget = |index: &mut PackageIndex| {
+            if let Some(to) = moved.get(&index.index) {
+                index.index = *to
+            }
+        };
+        if let Some(norm) = ex.get_normal_export_mut() {
+            for prop in norm.properties.iter_mut() {
+                on_prop_refs(prop, &mut retarget);
+            }
+        }
+        let base = ex.get_base_export_mut();
+        for deps in [
+            &mut base.serialization_before_serialization_dependencies,
+            &mut base.create_before_serialization_dependencies,
+            &mut base.serialization_before_create_dependencies,
+            &mut base.create_before_create_dependencies,
+        ] {
+            deps.iter_mut().for_each(&mut retarget)
+        }
+    }
+}
+
+/// actors attached to any of the actors and the ones attached to those
+pub fn attached(map: &Asset, actors: &[&Actor]) -> Vec<Actor> {
+    let mut owned = owned(map, actors);
+    let mut rest: Vec<_> = get_actors(map)
+        .into_iter()
+        .filter(|index| !owned.contains_key(&index.index))
+        .filter_map(|index| Actor::new(map, index).ok())
+        .map(|(_, actor)| actor)
+        .collect();
+    let mut found = Vec::new();
+    loop {
+        let (children, others): (Vec<_>, Vec<_>) = rest.into_iter().partition(|actor| {
+            actor
+                .parent(map)
+                .is_some_and(|parent| owned.contains_key(&(parent as i32 + 1)))
+        });
+        if children.is_empty() {
+            break;
+        }
+        for child in children.iter() {
+            for index in child.export_indices(map) {
+                owned.insert(index.index, child.export);
+            }
+        }
+        found.extend(children);
+        rest = others;
+    }
+    found
+}
+
+#[test]
+fn unlinked() {
+    let mut map = crate::regression::sample();
+    let actors = crate::regression::stand_ins(&mut map);
+    let [a, b, ..] = actors.as_slice() else {
+        panic!("the sample needs two stand-ins")
+    };
+    crate::regression::point(&mut map, a.export, b.export);
+    let from = |map: &Asset, actors: &[&Actor]| {
+        incoming(map, actors)
+            .into_iter()
+            .any(|incoming| incoming.from == a.export && incoming.via == "Target")
+    };
+    assert!(from(&map, &[b]));
+    // references between actors going together don't dangle
+    assert!(!from(&map, &[a, b]));
+    unlink(&mut map, &[b]);
+    assert!(!from(&map, &[b]));
+    assert_eq!(
+        crate::regression::target(&map, a.export),
+        Some(PackageIndex::new(0))
+    );
+}
+
+#[test]
+fn retargeted() {
+    let mut map = crate::regression::sample();
+    let actors = crate::regression::stand_ins(&mut map);
+    let [a, b, ..] = actors.as_slice() else {
+        panic!("the sample needs two stand-ins")
+    };
+    // anything outside of both can hold the reference
+    let other = (0..map.asset_data.exports.len())
+        .find(|i| ![a.export, b.export].contains(i))
+        .unwrap();
+    crate::regression::point(&mut map, other, a.export);
+    retarget(&mut map, a, b);
+    assert_eq!(
+        crate::regression::target(&map, other),
+        Some(PackageIndex::new(b.export as i32 + 1))
+    );
+}
@@ -3,8 +3,18 @@ use super::*;
 impl Actor {
     /// adds an actor to a map where the actor is already present
     pub fn duplicate(&self, asset: &mut Asset, export_names: &mut Vec<String>) {
+        self.duplicate_grouped(asset, export_names, &Group::new())
+    }
+
+    /// duplicates with references to the rest of a group pointed at their copies
+    pub fn duplicate_grouped(
+        &self,
+        asset: &mut Asset,
+        export_names: &mut Vec<String>,
+        group: &Group,
+    ) {
         let len = asset.asset_data.exports.len();
-        let mut children = self.get_actor_exports(asset, len);
+        let mut children = self.get_group_exports(asset, len, group);
 
         // make sure the actor has a unique object name
         give_unique_name(&mut children[0].get_base_export_mut().object_name, asset);
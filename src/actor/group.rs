@@ -0,0 +1,100 @@
+use super::*;
+
+// actors copied together are a unit so references between them point at the copies
+// while references to anything else are left as they are
+
+/// original export indices to where their copies will be
+pub type Group = std::collections::HashMap<i32, i32>;
+
+/// works out where the exports of actors added one after another after offset will go
+pub fn group_of(actors: &[&Actor], source: &Asset, mut offset: usize) -> Group {
+    let mut group = Group::new();
+    for actor in actors {
+        for index in actor.export_indices(source) {
+            offset += 1;
+            group.insert(index.index, offset as i32);
+        }
+    }
+    group
+}
+
+/// duplicates actors together returning the exports of the copies
+pub fn duplicate_group(
+    actors: &[&Actor],
+    asset: &mut Asset,
+    export_names: &mut Vec<String>,
+) -> Vec<usize> {
+    let group = group_of(actors, asset, asset.asset_data.exports.len());
+    actors
+        .iter()
+        .map(|actor| {
+            let len = asset.asset_data.exports.len();
+            actor.duplicate_grouped(asset, export_names, &group);
+            len
+        })
+        .collect()
+}
+
+/// transplants actors together returning where each landed or why it couldn't be
+pub fn transplant_group(
+    actors: &[&Actor],
+    recipient: &mut Asset,
+    donor: &Asset,
+    export_names: &mut Vec<String>,
+    import_names: &mut Vec<String>,
+) -> Vec<Result<usize, Vec<String>>> {
+    // exports are converted once up front so the ones which can't be are left out
+    // before working out where the rest go
+    let converted: Vec<_> = actors
+        .iter()
+        .map(|actor| {
+            let mut children = actor.exports(donor);
+            match convert::mismatch(recipient, donor) {
+                true => convert::convert(&mut children, recipient, donor).map(|_| children),
+                false => Ok(children),
+            }
+        })
+        .collect();
+    let accepted: Vec<_> = actors
+        .iter()
+        .zip(converted.iter())
+        .filter(|(_, converted)| converted.is_ok())
+        .map(|(actor, _)| *actor)
+        .collect();
+    let group = group_of(&accepted, donor, recipient.asset_data.exports.len());
+    actors
+        .iter()
+        .zip(converted)
+        .map(|(actor, converted)| {
+            let mut children = converted?;
+            let len = recipient.asset_data.exports.len();
+            actor.remap(donor, &mut children, len, &group);
+            actor.transplant_exports(recipient, donor, children, export_names, import_names);
+            Ok(len)
+        })
+        .collect()
+}
+
+#[test]
+fn grouped() {
+    let mut map = crate::regression::sample();
+    let actors = crate::regression::stand_ins(&mut map);
+    let [a, b, ..] = actors.as_slice() else {
+        panic!("the sample needs two stand-ins")
+    };
+    crate::regression::point(&mut map, a.export, b.export);
+    let len = map.asset_data.exports.len();
+    let copies = duplicate_group(&[a, b], &mut map, &mut Vec::new());
+    assert_eq!(copies, [len, len + 1]);
+    // copied together the reference follows
+    assert_eq!(
+        crate::regression::target(&map, copies[0]),
+        Some(PackageIndex::new(copies[1] as i32 + 1))
+    );
+    // and on its own it's left pointing at the original
+    let copy = duplicate_group(&[a], &mut map, &mut Vec::new())[0];
+    assert_eq!(
+        crate::regression::target(&map, copy),
+        Some(PackageIndex::new(b.export as i32 + 1))
+    );
+}
@@ -0,0 +1,108 @@
+use super::*;
+
+// where the replacement sits and what it's attached to are its own
+const KEEP: [&str; 6] = [
+    LOCATION,
+    ROTATION,
+    SCALE,
+    "RootComponent",
+    "AttachParent",
+    "AttachChildren",
+];
+
+fn same(a: &Property, b: &Property) -> bool {
+    a.get_name() == b.get_name() && a.get_duplication_index() == b.get_duplication_index()
+}
+
+/// copies properties from one export to another returning how many were
+fn carry(map: &mut Asset, from: usize, to: usize) -> usize {
+    let Some(source) = map.asset_data.exports[from].get_normal_export() else {
+        return 0;
+    };
+    let props: Vec<_> = source
+        .properties
+        .iter()
+        .filter(|prop| !KEEP.iter().any(|keep| prop.get_name() == *keep))
+        // references would point into the original which is about to go
+        .filter(|prop| {
+            let mut refs = false;
+            on_prop_refs(&mut (*prop).clone(), &mut |index| refs |= index.is_export());
+            !refs
+        })
+        .cloned()
+        .collect();
+    // unset properties are defaults so they only carry between the same class
+    let class = source.base_export.class_index;
+    let Some(norm) = map.asset_data.exports[to].get_normal_export_mut() else {
+        return 0;
+    };
+    let alike = norm.base_export.class_index == class;
+    let mut carried = 0;
+    for prop in props {
+        match norm.properties.iter_mut().find(|old| same(old, &prop)) {
+            Some(old) if std::mem::discriminant(old) == std::mem::discriminant(&prop) => {
+                *old = prop
+            }
+            None if alike => norm.properties.push(prop),
+            _ => continue,
+        }
+        carried += 1;
+    }
+    carried
+}
+
+impl Actor {
+    /// copies properties with matching names onto a replacement and its components of the same name
+    pub fn carry_over(&self, map: &mut Asset, to: &Actor) -> usize {
+        let mut carried = carry(map, self.export, to.export);
+        let theirs = to.components(map);
+        for component in self.components(map) {
+            let name = |export: usize| {
+                map.asset_data.exports[export]
+                    .get_base_export()
+                    .object_name
+                    .get_owned_content()
+            };
+            let mine = name(component);
+            let other = theirs.iter().copied().find(|other| name(*other) == mine);
+            if let Some(other) = other {
+                carried += carry(map, component, other)
+            }
+        }
+        carried
+    }
+}
+
+#[test]
+fn carried() {
+    let mut map = crate::regression::sample();
+    let actors = crate::regression::stand_ins(&mut map);
+    let [a, b, ..] = actors.as_slice() else {
+        panic!("the sample needs two stand-ins")
+    };
+    let mut names = map.get_name_map();
+    // set on both so it carries whatever their classes are
+    for (actor, val) in [(a, 2.0), (b, 1.0)] {
+        let norm = map.asset_data.exports[actor.export]
+            .get_normal_export_mut()
+            .unwrap();
+        crate::props::set_float(&mut norm.properties, "Intensity", val, &mut names);
+    }
+    crate::regression::point(&mut map, a.export, b.export);
+    let location = |map: &Asset, actor: &Actor| {
+        let norm = map.asset_data.exports[actor.export].get_normal_export();
+        crate::props::vector(&norm.unwrap().properties, LOCATION)
+    };
+    let before = location(&map, b);
+    assert!(a.carry_over(&mut map, b) >= 1);
+    let norm = map.asset_data.exports[b.export]
+        .get_normal_export()
+        .unwrap();
+    assert_eq!(
+        crate::props::float(&norm.properties, "Intensity"),
+        Some(2.0)
+    );
+    // the replacement keeps its own place and references into the original are left
+    assert_eq!(location(&map, b), before);
+    assert_eq!(crate::regression::target(&map, b.export), None);
+}
@@ -9,25 +9,19 @@ use unreal_asset::{
 
 impl Actor {
     pub fn location(&self, map: &Asset) -> bevy::math::Vec3 {
-        map.asset_data.exports[self.transform]
-            .get_normal_export()
-            .and_then(|norm| {
-                norm.properties.iter().rev().find_map(|prop| {
-                    if let Property::StructProperty(struc) = prop {
-                        if struc.name == LOCATION {
-                            return cast!(Property, VectorProperty, &struc.value[0]);
-                        }
-                    }
-                    None
-                })
-            })
-            .map(|pos| {
-                bevy::math::dvec3(pos.value.x.0, pos.value.z.0, pos.value.y.0).as_vec3() * 0.01
-            })
-            .unwrap_or_default()
+        location_of(map, self.transform)
     }
 
+    /// offsets the actor by a world space offset
     pub fn add_location(&self, map: &mut Asset, offset: bevy::math::Vec3) {
+        // attached actors are offset relative to their parent unless they ignore where it is
+        let offset = match absolute(map, self.transform, "Location") {
+            true => offset,
+            false => {
+                let parent = parent_world_of(map, self.transform);
+                parent.rotation.inverse() * offset / parent.scale
+            }
+        };
         let mut names = map.get_name_map();
         let Some(norm) = map.asset_data.exports[self.transform].get_normal_export_mut() else {
             return;
@@ -79,35 +73,18 @@ impl Actor {
     }
 
     pub fn rotation(&self, map: &Asset) -> bevy::math::Quat {
-        map.asset_data.exports[self.transform]
-            .get_normal_export()
-            .map(|norm| {
-                norm.properties
-                    .iter()
-                    .rev()
-                    .find_map(|prop| {
-                        if let Property::StructProperty(struc) = prop {
-                            if struc.name == ROTATION {
-                                return cast!(Property, RotatorProperty, &struc.value[0]);
-                            }
-                        }
-                        None
-                    })
-                    .map(|rot| {
-                        bevy::math::DQuat::from_euler(
-                            bevy::math::EulerRot::XYZ,
-                            rot.value.x.0.to_radians(),
-                            -rot.value.y.0.to_radians(),
-                            rot.value.z.0.to_radians(),
-                        )
-                        .as_quat()
-                    })
-                    .unwrap_or_default()
-            })
-            .unwrap_or_default()
+        rotation_of(map, self.transform)
     }
 
+    /// rotates the actor by a world space rotation
     pub fn combine_rotation(&self, map: &mut Asset, offset: bevy::math::Quat) {
+        let offset = match absolute(map, self.transform, "Rotation") {
+            true => offset,
+            false => {
+                let parent = parent_world_of(map, self.transform).rotation;
+                parent.inverse() * offset * parent
+            }
+        };
         let mut names = map.get_name_map();
         let Some(norm) = map.asset_data.exports[self.transform].get_normal_export_mut() else {
             return;
@@ -169,23 +146,24 @@ impl Actor {
     }
 
     pub fn scale(&self, map: &Asset) -> bevy::math::Vec3 {
-        map.asset_data.exports[self.transform]
-            .get_normal_export()
-            .and_then(|norm| {
-                norm.properties.iter().rev().find_map(|prop| {
-                    if let Property::StructProperty(struc) = prop {
-                        if struc.name == SCALE {
-                            return cast!(Property, VectorProperty, &struc.value[0]);
-                        }
-                    }
-                    None
-                })
-            })
-            .map(|rot| bevy::math::dvec3(rot.value.x.0, rot.value.z.0, rot.value.y.0).as_vec3())
-            .unwrap_or(bevy::math::Vec3::ONE)
+        scale_of(map, self.transform)
     }
 
+    /// scales the actor along world space axes
     pub fn mul_scale(&self, map: &mut Asset, offset: bevy::math::Vec3) {
+        // how far each of the parent's axes is stretched when it's turned from the world's
+        let offset = match absolute(map, self.transform, "Scale") {
+            true => offset,
+            false => {
+                let parent = parent_world_of(map, self.transform).rotation;
+                let axis = |axis: bevy::math::Vec3| (offset * (parent * axis)).length();
+                bevy::math::vec3(
+                    axis(bevy::math::Vec3::X),
+                    axis(bevy::math::Vec3::Y),
+                    axis(bevy::math::Vec3::Z),
+                )
+            }
+        };
         let mut names = map.get_name_map();
         let Some(norm) = map.asset_data.exports[self.transform].get_normal_export_mut() else {
             return;
@@ -236,11 +214,158 @@ impl Actor {
         }
     }
 
+    /// gets the world transform of the actor
     pub fn transform(&self, map: &Asset) -> bevy::prelude::Transform {
-        bevy::prelude::Transform {
-            translation: self.location(map),
-            rotation: self.rotation(map),
-            scale: self.scale(map),
+        world_of(map, self.transform)
+    }
+
+    /// gets the actor's attach parent if it has one
+    pub fn parent(&self, map: &Asset) -> Option<usize> {
+        parent_of(map, self.transform)
+    }
+
+    /// gets the components the actor is attached under from nearest to furthest
+    pub fn ancestors(&self, map: &Asset) -> Vec<usize> {
+        let mut ancestors = Vec::new();
+        let mut current = self.transform;
+        // the limit guards against cyclic attachments in broken maps
+        while let Some(parent) = parent_of(map, current).filter(|_| ancestors.len() < 64) {
+            ancestors.push(parent);
+            current = parent
         }
+        ancestors
     }
 }
+
+/// gets the relative location of any scene component
+pub fn location_of(map: &Asset, export: usize) -> bevy::math::Vec3 {
+    map.asset_data.exports[export]
+        .get_normal_export()
+        .and_then(|norm| {
+            norm.properties.iter().rev().find_map(|prop| {
+                if let Property::StructProperty(struc) = prop {
+                    if struc.name == LOCATION {
+                        return cast!(Property, VectorProperty, &struc.value[0]);
+                    }
+                }
+                None
+            })
+        })
+        .map(|pos| bevy::math::dvec3(pos.value.x.0, pos.value.z.0, pos.value.y.0).as_vec3() * 0.01)
+        .unwrap_or_default()
+}
+
+/// gets the relative rotation of any scene component
+pub fn rotation_of(map: &Asset, export: usize) -> bevy::math::Quat {
+    map.asset_data.exports[export]
+        .get_normal_export()
+        .map(|norm| {
+            norm.properties
+                .iter()
+                .rev()
+                .find_map(|prop| {
+                    if let Property::StructProperty(struc) = prop {
+                        if struc.name == ROTATION {
+                            return cast!(Property, RotatorProperty, &struc.value[0]);
+                        }
+                    }
+                    None
+                })
+                .map(|rot| {
+                    bevy::math::DQuat::from_euler(
+                        bevy::math::EulerRot::XYZ,
+                        rot.value.x.0.to_radians(),
+                        -rot.value.y.0.to_radians(),
+                        rot.value.z.0.to_radians(),
+                    )
+                    .as_quat()
+                })
+                .unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+/// gets the relative scale of any scene component
+pub fn scale_of(map: &Asset, export: usize) -> bevy::math::Vec3 {
+    map.asset_data.exports[export]
+        .get_normal_export()
+        .and_then(|norm| {
+            norm.properties.iter().rev().find_map(|prop| {
+                if let Property::StructProperty(struc) = prop {
+                    if struc.name == SCALE {
+                        return cast!(Property, VectorProperty, &struc.value[0]);
+                    }
+                }
+                None
+            })
+        })
+        .map(|rot| bevy::math::dvec3(rot.value.x.0, rot.value.z.0, rot.value.y.0).as_vec3())
+        .unwrap_or(bevy::math::Vec3::ONE)
+}
+
+/// gets the relative transform of any scene component
+pub fn transform_of(map: &Asset, export: usize) -> bevy::prelude::Transform {
+    bevy::prelude::Transform {
+        translation: location_of(map, export),
+        rotation: rotation_of(map, export),
+        scale: scale_of(map, export),
+    }
+}
+
+/// gets the component the given component is attached to
+pub fn parent_of(map: &Asset, export: usize) -> Option<usize> {
+    map.asset_data.exports[export]
+        .get_normal_export()?
+        .properties
+        .iter()
+        .find(|prop| prop.get_name() == "AttachParent")
+        .and_then(|prop| cast!(Property, ObjectProperty, prop))
+        .filter(|obj| obj.value.is_export())
+        .map(|obj| obj.value.index as usize - 1)
+        .filter(|parent| parent != &export && parent < &map.asset_data.exports.len())
+}
+
+/// whether the component ignores its parent's location, rotation or scale
+fn absolute(map: &Asset, export: usize, kind: &str) -> bool {
+    map.asset_data.exports[export]
+        .get_normal_export()
+        .is_some_and(|norm| {
+            norm.properties.iter().any(|prop| {
+                // renamed in ue5
+                (prop.get_name() == format!("bAbsolute{kind}").as_str()
+                    || prop.get_name() == format!("bUsingAbsolute{kind}").as_str())
+                    && cast!(Property, BoolProperty, prop).is_some_and(|bool| bool.value)
+            })
+        })
+}
+
+fn resolve(map: &Asset, export: usize, depth: usize) -> bevy::prelude::Transform {
+    let relative = transform_of(map, export);
+    // the depth limit guards against cyclic attachments in broken maps
+    let Some(parent) = parent_of(map, export).filter(|_| depth < 64) else {
+        return relative;
+    };
+    let mut world = resolve(map, parent, depth + 1).mul_transform(relative);
+    if absolute(map, export, "Location") {
+        world.translation = relative.translation
+    }
+    if absolute(map, export, "Rotation") {
+        world.rotation = relative.rotation
+    }
+    if absolute(map, export, "Scale") {
+        world.scale = relative.scale
+    }
+    world
+}
+
+/// resolves the attachment chain to get the world transform of any scene component
+pub fn world_of(map: &Asset, export: usize) -> bevy::prelude::Transform {
+    resolve(map, export, 0)
+}
+
+/// gets the world transform of whatever the component is attached to
+pub fn parent_world_of(map: &Asset, export: usize) -> bevy::prelude::Transform {
+    parent_of(map, export)
+        .map(|parent| world_of(map, parent))
+        .unwrap_or_default()
+}
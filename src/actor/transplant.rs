@@ -1,6 +1,41 @@
 use super::*;
 use unreal_asset::{cast, types::fname::ToSerializedName, Import};
 
+pub enum Status {
+    New,
+    Conflict,
+}
+
+/// the exports, imports and names a transplant brings
+pub struct Report {
+    pub exports: Vec<String>,
+    pub imports: Vec<(String, Status)>,
+    pub names: Vec<String>,
+    // why it can't be converted from the donor's version
+    pub problems: Vec<String>,
+}
+
+fn same(a: &Import, b: &Import) -> bool {
+    a.class_package.eq_content(&b.class_package)
+        && a.class_name.eq_content(&b.class_name)
+        && a.object_name.eq_content(&b.object_name)
+}
+
+/// whether an import already in the recipient is nested the same as the donor's so it can be used
+fn reusable(donor: &Asset, import: &Import, recipient: &Asset, existing: i32) -> bool {
+    let existing = recipient
+        .get_import(PackageIndex::new(existing))
+        .map(|existing| existing.outer_index);
+    match (donor.get_import(import.outer_index), existing) {
+        (Some(outer), Some(existing)) => recipient
+            .get_import(existing)
+            .is_some_and(|existing| same(&outer, &existing)),
+        // top-level packages aren't nested in anything
+        (None, Some(existing)) => import.outer_index.index == 0 && existing.index == 0,
+        _ => false,
+    }
+}
+
 impl Actor {
     pub fn transplant(
         &self,
@@ -8,9 +43,37 @@ impl Actor {
         donor: &Asset,
         export_names: &mut Vec<String>,
         import_names: &mut Vec<String>,
-    ) {
-        let mut children = self.get_actor_exports(donor, recipient.asset_data.exports.len());
+    ) -> Result<(), Vec<String>> {
+        self.transplant_grouped(recipient, donor, export_names, import_names, &Group::new())
+    }
+
+    /// transplants with references to the rest of a group pointed at their copies
+    pub fn transplant_grouped(
+        &self,
+        recipient: &mut Asset,
+        donor: &Asset,
+        export_names: &mut Vec<String>,
+        import_names: &mut Vec<String>,
+        group: &Group,
+    ) -> Result<(), Vec<String>> {
+        let mut children = self.get_group_exports(donor, recipient.asset_data.exports.len(), group);
+        // exports from other versions are converted before anything's added
+        if convert::mismatch(recipient, donor) {
+            convert::convert(&mut children, recipient, donor)?;
+        }
+        self.transplant_exports(recipient, donor, children, export_names, import_names);
+        Ok(())
+    }
 
+    /// adds exports which already point at where they'll go and suit the recipient's version
+    pub fn transplant_exports(
+        &self,
+        recipient: &mut Asset,
+        donor: &Asset,
+        mut children: Vec<crate::Export>,
+        export_names: &mut Vec<String>,
+        import_names: &mut Vec<String>,
+    ) {
         // make sure the actor has a unique object name
         give_unique_name(
             &mut children[0].get_base_export_mut().object_name,
@@ -57,20 +120,7 @@ impl Actor {
                 &import.object_name,
             ) {
                 // sometimes e.g for GEN_VARIABLEs you want those imports
-                Some(existing)
-                    if donor.get_import(import.outer_index).is_some_and(|imp| {
-                        recipient
-                            .get_import(PackageIndex::new(existing))
-                            .and_then(|existing| recipient.get_import(existing.outer_index))
-                            .is_some_and(|import| {
-                                imp.class_package.eq_content(&import.class_package)
-                                    && imp.class_name.eq_content(&import.class_name)
-                                    && imp.object_name.eq_content(&import.object_name)
-                            })
-                    }) =>
-                {
-                    existing
-                }
+                Some(existing) if reusable(donor, &import, recipient, existing) => existing,
                 _ => {
                     -import_offset
                         - match imports.iter().position(|imp: &Import| {
@@ -91,7 +141,11 @@ impl Actor {
         for child in children.iter_mut() {
             on_import_refs(child, &mut resolve)
         }
-        if donor.get_engine_version() >= unreal_asset::engine_version::EngineVersion::VER_UE5_1 {
+        // the block is only left when both have it
+        if donor.get_engine_version() >= unreal_asset::engine_version::EngineVersion::VER_UE5_1
+            && recipient.get_engine_version()
+                >= unreal_asset::engine_version::EngineVersion::VER_UE5_1
+        {
             for child in children[1..]
                 .iter_mut()
                 .filter(|child| child.get_base_export().object_name != "Model")
@@ -135,20 +189,7 @@ impl Actor {
                 &parent.class_name,
                 &parent.object_name,
             ) {
-                Some(existing)
-                    if donor.get_import(parent.outer_index).is_some_and(|imp| {
-                        recipient
-                            .get_import(PackageIndex::new(existing))
-                            .and_then(|existing| recipient.get_import(existing.outer_index))
-                            .is_some_and(|import| {
-                                imp.class_package.eq_content(&import.class_package)
-                                    && imp.class_name.eq_content(&import.class_name)
-                                    && imp.object_name.eq_content(&import.object_name)
-                            })
-                    }) =>
-                {
-                    existing
-                }
+                Some(existing) if reusable(donor, &parent, recipient, existing) => existing,
                 _ => {
                     -import_offset
                         - match imports.iter().position(|import: &Import| {
@@ -170,10 +211,176 @@ impl Actor {
         import_names.extend(imports.iter().map(|im| im.object_name.get_owned_content()));
         recipient.imports.append(&mut imports);
     }
+
+    /// what transplanting would add to the recipient without touching it
+    pub fn report(&self, recipient: &Asset, donor: &Asset) -> Report {
+        use byteorder::{ReadBytesExt, LE};
+        let mut children = self.get_actor_exports(donor, recipient.asset_data.exports.len());
+        let problems = match convert::mismatch(recipient, donor) {
+            true => convert::convert(&mut children, recipient, donor).err(),
+            false => None,
+        }
+        .unwrap_or_default();
+        let exports = children
+            .iter()
+            .map(|ex| ex.get_base_export().object_name.get_owned_content())
+            .collect();
+        let mut refs: Vec<PackageIndex> = Vec::new();
+        let mut push = |index: PackageIndex| {
+            if index.is_import() && !refs.contains(&index) {
+                refs.push(index)
+            }
+        };
+        for child in children.iter_mut() {
+            on_import_refs(child, &mut |index| push(*index))
+        }
+        if donor.get_engine_version() >= unreal_asset::engine_version::EngineVersion::VER_UE5_1
+            && recipient.get_engine_version()
+                >= unreal_asset::engine_version::EngineVersion::VER_UE5_1
+        {
+            // read the same references transplanting rewrites in the extras
+            for norm in children[1..]
+                .iter()
+                .filter(|child| child.get_base_export().object_name != "Model")
+                .filter_map(ExportNormalTrait::get_normal_export)
+            {
+                let mut data = std::io::Cursor::new(&norm.extras);
+                let _ = data.read_i32::<LE>();
+                for _ in 0..data.read_u32::<LE>().unwrap_or_default() {
+                    let Ok(index) = data.read_i32::<LE>() else {
+                        break;
+                    };
+                    push(PackageIndex::new(index));
+                    let _ = data.read_i32::<LE>();
+                }
+            }
+        }
+        // outers come along too
+        let mut i = 0;
+        while i < refs.len() {
+            if let Some(outer) = donor.get_import(refs[i]).map(|import| import.outer_index) {
+                if outer.is_import() && !refs.contains(&outer) {
+                    refs.push(outer)
+                }
+            }
+            i += 1;
+        }
+        let mut names = Vec::new();
+        let mut imports = Vec::new();
+        for import in refs.iter().filter_map(|index| donor.get_import(*index)) {
+            let status = match recipient.find_import_no_index(
+                &import.class_package,
+                &import.class_name,
+                &import.object_name,
+            ) {
+                Some(existing) if reusable(donor, &import, recipient, existing) => continue,
+                // a differently nested import of the same name gets a duplicate
+                Some(_) => Status::Conflict,
+                None => Status::New,
+            };
+            for name in [
+                &import.class_package,
+                &import.class_name,
+                &import.object_name,
+            ] {
+                names.push(name.get_owned_content())
+            }
+            imports.push((
+                format!(
+                    "{} {}",
+                    import.class_name.get_owned_content(),
+                    import.object_name.get_owned_content()
+                ),
+                status,
+            ))
+        }
+        for child in children.iter() {
+            names.push(child.get_base_export().object_name.get_owned_content());
+            if let Some(norm) = child.get_normal_export() {
+                for prop in norm.properties.iter() {
+                    names_of(prop, &mut names)
+                }
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        names.retain(|name| {
+            recipient.search_name_reference(name).is_none() && seen.insert(name.clone())
+        });
+        Report {
+            exports,
+            imports,
+            names,
+            problems,
+        }
+    }
+}
+
+/// the names a property brings along with those of everything inside it
+fn names_of(prop: &Property, names: &mut Vec<String>) {
+    names.push(prop.get_name().get_owned_content());
+    match prop {
+        Property::StructProperty(struc) => {
+            names.extend(struc.struct_type.as_ref().map(FName::get_owned_content));
+            for entry in struc.value.iter() {
+                names_of(entry, names)
+            }
+        }
+        Property::ArrayProperty(arr) => {
+            for entry in arr.value.iter() {
+                names_of(entry, names)
+            }
+        }
+        Property::SetProperty(set) => {
+            for entry in set.value.value.iter() {
+                names_of(entry, names)
+            }
+        }
+        Property::MapProperty(map) => {
+            for (key, val) in map.value.iter() {
+                names_of(key, names);
+                names_of(val, names)
+            }
+        }
+        Property::NameProperty(name) => names.push(name.value.get_owned_content()),
+        Property::EnumProperty(enm) => {
+            names.extend(enm.value.as_ref().map(FName::get_owned_content))
+        }
+        _ => (),
+    }
+}
+
+#[test]
+fn report() {
+    let mut donor = crate::regression::sample();
+    let actor = crate::regression::stand_ins(&mut donor).remove(0);
+    let mut names = donor.get_name_map();
+    let norm = donor.asset_data.exports[actor.export]
+        .get_normal_export_mut()
+        .unwrap();
+    crate::props::set_float(&mut norm.properties, "Intensity", 8.0, &mut names);
+    // everything's already there in its own package
+    let report = actor.report(&donor, &donor);
+    assert_eq!(report.exports.len(), 1);
+    assert!(report.imports.is_empty() && report.names.is_empty());
+    let mut recipient = crate::regression::sample();
+    let report = actor.report(&recipient, &donor);
+    assert!(report.imports.is_empty());
+    assert!(["Intensity", LOCATION]
+        .iter()
+        .all(|name| report.names.iter().any(|new| new == name)));
+    let imports = recipient.imports.len();
+    actor
+        .transplant(&mut recipient, &donor, &mut Vec::new(), &mut Vec::new())
+        .unwrap();
+    recipient.rebuild_name_map();
+    assert_eq!(recipient.imports.len(), imports + report.imports.len());
+    for name in report.names.iter() {
+        assert!(recipient.search_name_reference(name).is_some(), "{name}")
+    }
 }
 
 /// on all of an export's possible references to imports
-fn on_import_refs(export: &mut crate::Export, func: &mut impl FnMut(&mut PackageIndex)) {
+pub fn on_import_refs(export: &mut crate::Export, func: &mut impl FnMut(&mut PackageIndex)) {
     if let Some(norm) = export.get_normal_export_mut() {
         for prop in norm.properties.iter_mut() {
             on_prop_refs(prop, func);
@@ -202,6 +409,18 @@ fn on_extra_import_refs(
     mut recipient: unreal_asset::containers::SharedResource<unreal_asset::containers::NameMap>,
     export: &mut crate::Export,
     func: &mut impl FnMut(&mut PackageIndex),
+) {
+    on_extra_refs(export, &mut |index, name| {
+        func(index);
+        let content = donor.get_ref().get_name_reference(*name).to_string();
+        *name = recipient.get_mut().add_name_reference(content, false);
+    })
+}
+
+/// on the import references and their names in a 5.1+ component's extras
+pub fn on_extra_refs(
+    export: &mut crate::Export,
+    func: &mut impl FnMut(&mut PackageIndex, &mut i32),
 ) {
     use byteorder::{ReadBytesExt, WriteBytesExt, LE};
     use std::io::Seek;
@@ -210,16 +429,14 @@ fn on_extra_import_refs(
     };
     let mut data = std::io::Cursor::new(&mut norm.extras);
     let _ = data.read_i32::<LE>();
-    for _ in 0..data.read_u32::<LE>().unwrap() {
-        let mut index = PackageIndex::new(data.read_i32::<LE>().unwrap());
-        func(&mut index);
-        let _ = data.seek(std::io::SeekFrom::Current(-4));
+    for _ in 0..data.read_u32::<LE>().unwrap_or_default() {
+        let (Ok(index), Ok(mut name)) = (data.read_i32::<LE>(), data.read_i32::<LE>()) else {
+            break;
+        };
+        let mut index = PackageIndex::new(index);
+        func(&mut index, &mut name);
+        let _ = data.seek(std::io::SeekFrom::Current(-8));
         let _ = data.write_i32::<LE>(index.index);
-        let name = donor
-            .get_ref()
-            .get_name_reference(data.read_i32::<LE>().unwrap())
-            .to_string();
-        let _ = data.seek(std::io::SeekFrom::Current(-4));
-        let _ = data.write_i32::<LE>(recipient.get_mut().add_name_reference(name, false));
+        let _ = data.write_i32::<LE>(name);
     }
 }
@@ -56,6 +56,11 @@ impl Actor {
                 });
             }
         }
+        // edits are relative so resolve the attachment chain again
+        let world = self.transform(asset);
+        if *transform != world {
+            *transform = world
+        }
     }
 }
 
@@ -167,7 +172,7 @@ fn text(ui: &mut egui::Ui, val: &mut String) {
     egui::TextEdit::singleline(val).clip_text(false).show(ui);
 }
 
-fn fname(ui: &mut egui::Ui, name: &mut FName) {
+pub fn fname(ui: &mut egui::Ui, name: &mut FName) {
     match name {
         FName::Backed {
             index, name_map, ..
@@ -0,0 +1,247 @@
+use super::*;
+
+// copies of the selection laid out in a pattern where each copy is a step on from the last
+
+#[derive(PartialEq, Clone, Copy)]
+enum Layout {
+    Linear,
+    Radial,
+    Grid,
+}
+
+#[derive(Resource)]
+pub struct Array {
+    pub open: bool,
+    layout: Layout,
+    count: usize,
+    // the offset between copies and the spacing of grid cells
+    step: Vec3,
+    // cells along each axis including the original's
+    cells: [usize; 3],
+    // radial copies go around this offset from the centre of the selection
+    pivot: Vec3,
+    angle: f32,
+    // rotation in degrees and scale added on with each copy
+    rotation: Vec3,
+    scale: Vec3,
+}
+
+impl Default for Array {
+    fn default() -> Self {
+        Self {
+            open: false,
+            layout: Layout::Linear,
+            count: 3,
+            step: Vec3::X * 2.0,
+            cells: [3, 1, 3],
+            pivot: Vec3::Z * 5.0,
+            angle: 45.0,
+            rotation: Vec3::ZERO,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+impl Array {
+    /// the pivot and offset of each copy relative to the centre of the selection
+    fn offsets(&self, centre: Vec3) -> Vec<(Vec3, Transform)> {
+        let steps: Vec<_> = match self.layout {
+            Layout::Linear => (1..=self.count)
+                .map(|i| (centre, self.step * i as f32, Quat::IDENTITY))
+                .collect(),
+            Layout::Radial => (1..=self.count)
+                .map(|i| {
+                    (
+                        centre + self.pivot,
+                        Vec3::ZERO,
+                        Quat::from_rotation_y((self.angle * i as f32).to_radians()),
+                    )
+                })
+                .collect(),
+            Layout::Grid => {
+                let [x, y, z] = self.cells;
+                (0..x)
+                    .flat_map(|i| (0..y).flat_map(move |j| (0..z).map(move |k| (i, j, k))))
+                    // the original is already in the first cell
+                    .skip(1)
+                    .map(|(i, j, k)| {
+                        (
+                            centre,
+                            self.step * Vec3::new(i as f32, j as f32, k as f32),
+                            Quat::IDENTITY,
+                        )
+                    })
+                    .collect()
+            }
+        };
+        let [x, y, z] = self.rotation.to_array().map(f32::to_radians);
+        let rotation = Quat::from_euler(EulerRot::XYZ, x, y, z);
+        let (mut spin, mut size) = (Quat::IDENTITY, Vec3::ONE);
+        steps
+            .into_iter()
+            .map(|(pivot, translation, turn)| {
+                spin = rotation * spin;
+                size *= self.scale;
+                (
+                    pivot,
+                    Transform {
+                        translation,
+                        rotation: turn * spin,
+                        scale: size,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+pub fn toggle(_: Trigger<triggers::ArrayTool>, mut array: ResMut<Array>) {
+    array.open = !array.open
+}
+
+fn axes(ui: &mut egui::Ui, label: &str, value: &mut Vec3, speed: f32) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.add(
+            egui::DragValue::new(&mut value.x)
+                .speed(speed)
+                .prefix("x: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut value.y)
+                .speed(speed)
+                .prefix("y: "),
+        );
+        ui.add(
+            egui::DragValue::new(&mut value.z)
+                .speed(speed)
+                .prefix("z: "),
+        );
+    });
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut array: ResMut<Array>,
+    selected: Query<(), With<actor::Selected>>,
+) {
+    if !array.open {
+        return;
+    }
+    let mut open = true;
+    let array = array.as_mut();
+    egui::Window::new("array")
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut array.layout, Layout::Linear, "linear");
+                ui.selectable_value(&mut array.layout, Layout::Radial, "radial");
+                ui.selectable_value(&mut array.layout, Layout::Grid, "grid");
+            });
+            match array.layout {
+                Layout::Linear => {
+                    ui.add(egui::Slider::new(&mut array.count, 1..=100).text("copies"));
+                    axes(ui, "offset", &mut array.step, 0.1);
+                }
+                Layout::Radial => {
+                    ui.add(egui::Slider::new(&mut array.count, 1..=100).text("copies"));
+                    axes(ui, "pivot", &mut array.pivot, 0.1);
+                    ui.add(
+                        egui::DragValue::new(&mut array.angle)
+                            .speed(1.0)
+                            .prefix("angle: ")
+                            .suffix("°"),
+                    );
+                }
+                Layout::Grid => {
+                    ui.horizontal(|ui| {
+                        ui.label("cells");
+                        for (cells, axis) in array.cells.iter_mut().zip(["x: ", "y: ", "z: "]) {
+                            ui.add(egui::DragValue::new(cells).range(1..=50).prefix(axis));
+                        }
+                    });
+                    axes(ui, "spacing", &mut array.step, 0.1);
+                }
+            }
+            ui.separator();
+            ui.label("added with each copy");
+            axes(ui, "rotation", &mut array.rotation, 1.0);
+            axes(ui, "scale", &mut array.scale, 0.01);
+            ui.add_enabled_ui(!selected.is_empty(), |ui| {
+                if ui
+                    .button("make copies")
+                    .on_hover_text("copies the selection")
+                    .clicked()
+                {
+                    commands.trigger(triggers::MakeArray)
+                }
+            });
+        });
+    array.open &= open;
+}
+
+pub fn spawn(
+    _: Trigger<triggers::MakeArray>,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    array: Res<Array>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    mut map: NonSendMut<Map>,
+    selected: Query<(Entity, &actor::Actor), With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+) {
+    let Some((map, _, export_names, _)) = &mut map.0 else {
+        return;
+    };
+    let actors: Vec<_> = selected.iter().map(|(_, actor)| actor).collect();
+    // attached actors move with their parent so only the rest make up the centre
+    let roots: Vec<_> = actors
+        .iter()
+        .filter(|actor| actor.parent(map).is_none())
+        .map(|actor| actor.transform(map).translation)
+        .collect();
+    if roots.is_empty() {
+        notif.send(Notif {
+            message: "no actors to copy".into(),
+            kind: Warning,
+        });
+        return;
+    }
+    let centre = roots.iter().sum::<Vec3>() / roots.len() as f32;
+    for (entity, _) in selected.iter() {
+        outliner::deselect(&mut commands, &mut cubes, &consts, entity);
+    }
+    let mut batch = Vec::new();
+    let offsets = array.offsets(centre);
+    let copies = offsets.len();
+    for (pivot, offset) in offsets {
+        let copy = action::stamp(&mut commands, &mut notif, map, export_names, &actors);
+        action::arrange(&mut commands, map, &copy, pivot, offset);
+        batch.extend(copy);
+    }
+    dialog::spawn_new(
+        map,
+        batch,
+        &mut commands,
+        &mut registry,
+        &content,
+        &appdata,
+        &consts,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+    notif.send(Notif {
+        message: format!("made {copies} copies of {} actors", actors.len()),
+        kind: Success,
+    });
+}
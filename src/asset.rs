@@ -94,6 +94,52 @@ pub fn get<T>(
     None
 }
 
+/// makes an image from the first texture of a material which can be read
+pub fn material(
+    content: &super::Content,
+    cache: Option<&std::path::Path>,
+    path: &str,
+    version: unreal_asset::engine_version::EngineVersion,
+) -> Option<bevy::prelude::Image> {
+    use bevy::render::{render_resource::*, texture::*};
+    let paths = get(content, cache, path, version, |mat, _| {
+        Ok(super::extras::get_tex_paths(mat))
+    })?;
+    paths.into_iter().find_map(|path| {
+        let Some((false, width, height, data)) =
+            get(content, cache, &path, version, |tex, bulk| {
+                Ok(super::extras::get_tex_info(tex, bulk)?)
+            })
+        else {
+            return None;
+        };
+        Some(bevy::prelude::Image {
+            data,
+            texture_descriptor: TextureDescriptor {
+                label: None,
+                size: Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Bgra8UnormSrgb,
+                usage: TextureUsages::TEXTURE_BINDING,
+                view_formats: &[TextureFormat::Bgra8UnormSrgb],
+            },
+            sampler: ImageSampler::Descriptor(ImageSamplerDescriptor {
+                address_mode_u: ImageAddressMode::Repeat,
+                address_mode_v: ImageAddressMode::Repeat,
+                address_mode_w: ImageAddressMode::Repeat,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    })
+}
+
 fn read<T>(
     pak: &repak::PakReader,
     pak_file: &std::path::Path,
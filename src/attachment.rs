@@ -0,0 +1,58 @@
+use super::*;
+
+/// marks newly spawned actors which are attached to another component
+pub fn mark(
+    mut commands: Commands,
+    map: NonSend<Map>,
+    added: Query<(Entity, &actor::Actor), Added<actor::Actor>>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    for (entity, actor) in added.iter() {
+        if actor.parent(map).is_some() {
+            commands.entity(entity).insert(actor::Attached);
+        }
+    }
+}
+
+/// gets the actors attached somewhere under another of them which move along with it anyway
+pub fn followers(map: &Asset, actors: &[(Entity, &actor::Actor)]) -> Vec<Entity> {
+    let owned: std::collections::HashSet<_> = actors
+        .iter()
+        .flat_map(|(_, actor)| {
+            let mut exports = actor.components(map);
+            exports.extend([actor.export, actor.root()]);
+            exports
+        })
+        .collect();
+    actors
+        .iter()
+        .filter(|(_, actor)| {
+            actor
+                .ancestors(map)
+                .iter()
+                .any(|ancestor| owned.contains(ancestor))
+        })
+        .map(|(entity, _)| *entity)
+        .collect()
+}
+
+/// moves attached actors along with whatever they're attached to
+pub fn follow(
+    map: NonSend<Map>,
+    mut set: ParamSet<(
+        Query<(), (With<actor::Actor>, Changed<Transform>)>,
+        Query<(&actor::Actor, &mut Transform), With<actor::Attached>>,
+    )>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    if set.p0().is_empty() {
+        return;
+    }
+    for (actor, mut transform) in set.p1().iter_mut() {
+        let world = actor.transform(map);
+        // only write when different so this doesn't trigger itself every frame
+        if *transform != world {
+            *transform = world
+        }
+    }
+}
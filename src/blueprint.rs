@@ -0,0 +1,258 @@
+use super::props::find;
+use super::*;
+use unreal_asset::{
+    cast,
+    exports::{Export, ExportBaseTrait, ExportNormalTrait},
+    properties::{Property, PropertyDataTrait},
+    types::PackageIndexTrait,
+};
+
+/// a component a blueprint class constructs its actors with
+#[derive(Clone)]
+pub struct Template {
+    name: String,
+    parent: Option<String>,
+    mesh: Option<String>,
+    materials: Vec<Option<String>>,
+    transform: Option<Transform>,
+}
+
+/// gets the package of an actor's class if it's a blueprint
+pub fn class_path(map: &Asset, actor: &actor::Actor) -> Option<String> {
+    let class = map.get_import(
+        map.asset_data.exports[actor.export]
+            .get_base_export()
+            .class_index,
+    )?;
+    let path = map
+        .get_import(class.outer_index)?
+        .object_name
+        .get_owned_content();
+    // native classes have no construction script to read
+    (!path.starts_with("/Script/")).then_some(path)
+}
+
+/// reads the component templates of a class and the classes it inherits from
+pub fn templates(
+    content: &Content,
+    cache: Option<&std::path::Path>,
+    path: &str,
+    version: unreal_asset::engine_version::EngineVersion,
+) -> Vec<Template> {
+    inherit(content, cache, path, version, 0)
+}
+
+fn inherit(
+    content: &Content,
+    cache: Option<&std::path::Path>,
+    path: &str,
+    version: unreal_asset::engine_version::EngineVersion,
+    depth: usize,
+) -> Vec<Template> {
+    let Some((parent, nodes, overrides)) =
+        asset::get(content, cache, path, version, |class, _| Ok(read(&class)))
+    else {
+        return Vec::new();
+    };
+    // the depth limit guards against cyclic inheritance in broken packages
+    let mut templates = match parent.filter(|_| depth < 16) {
+        Some(parent) => inherit(content, cache, &parent, version, depth + 1),
+        None => Vec::new(),
+    };
+    // inherited components are overridden by name
+    for over in overrides {
+        match templates.iter_mut().find(|temp| temp.name == over.name) {
+            Some(temp) => {
+                if over.mesh.is_some() {
+                    temp.mesh = over.mesh
+                }
+                if !over.materials.is_empty() {
+                    temp.materials = over.materials
+                }
+                if over.transform.is_some() {
+                    temp.transform = over.transform
+                }
+            }
+            None => templates.push(over),
+        }
+    }
+    templates.extend(nodes);
+    templates
+}
+
+/// gets the parent class package, construction script nodes and inherited overrides
+fn read(class: &Asset) -> (Option<String>, Vec<Template>, Vec<Template>) {
+    let exports = &class.asset_data.exports;
+    let Some(generated) = exports.iter().find_map(|ex| cast!(Export, ClassExport, ex)) else {
+        return (None, Vec::new(), Vec::new());
+    };
+    let parent = class
+        .get_import(generated.struct_export.super_struct)
+        .and_then(|parent| class.get_import(parent.outer_index))
+        .map(|path| path.object_name.get_owned_content())
+        .filter(|path| !path.starts_with("/Script/"));
+    let object = |props: &[Property], key: &str| {
+        find(props, key)
+            .and_then(|prop| cast!(Property, ObjectProperty, prop))
+            .filter(|obj| obj.value.is_export())
+            .map(|obj| obj.value.index as usize - 1)
+            .filter(|&i| i < exports.len())
+    };
+    let objects = |props: &[Property], key: &str| -> Vec<usize> {
+        match find(props, key) {
+            Some(Property::ArrayProperty(arr)) => arr
+                .value
+                .iter()
+                .filter_map(|prop| cast!(Property, ObjectProperty, prop))
+                .filter(|obj| obj.value.is_export())
+                .map(|obj| obj.value.index as usize - 1)
+                .filter(|&i| i < exports.len())
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+    let name = |props: &[Property], key: &str| {
+        find(props, key)
+            .and_then(|prop| cast!(Property, NameProperty, prop))
+            .map(|name| name.value.get_owned_content())
+    };
+    let props = |i: usize| {
+        exports[i]
+            .get_normal_export()
+            .map(|norm| norm.properties.as_slice())
+            .unwrap_or_default()
+    };
+    let template = |var: String, parent: Option<String>, export: usize| Template {
+        name: var,
+        parent,
+        mesh: actor::mesh_of(class, export),
+        materials: actor::materials_of(class, export),
+        // properties left as default are cut so only override what's there
+        transform: [actor::LOCATION, actor::ROTATION, actor::SCALE]
+            .iter()
+            .any(|kind| find(props(export), kind).is_some())
+            .then(|| actor::transform_of(class, export)),
+    };
+    let generated = &generated.struct_export.normal_export.properties;
+
+    let mut nodes = Vec::new();
+    if let Some(scs) = object(generated, "SimpleConstructionScript") {
+        let all = objects(props(scs), "AllNodes");
+        // the hierarchy is stored top down so find each node's parent first
+        let mut parents = std::collections::BTreeMap::new();
+        for &node in all.iter() {
+            let Some(var) = name(props(node), "InternalVariableName") else {
+                continue;
+            };
+            for child in objects(props(node), "ChildNodes") {
+                parents.insert(child, var.clone());
+            }
+        }
+        for node in all {
+            let node_props = props(node);
+            let (Some(var), Some(export)) = (
+                name(node_props, "InternalVariableName"),
+                object(node_props, "ComponentTemplate"),
+            ) else {
+                continue;
+            };
+            let parent = parents
+                .remove(&node)
+                .or_else(|| name(node_props, "ParentComponentOrVariableName"));
+            nodes.push(template(var, parent, export));
+        }
+    }
+
+    let mut overrides = Vec::new();
+    if let Some(handler) = object(generated, "InheritableComponentHandler") {
+        if let Some(Property::ArrayProperty(records)) = find(props(handler), "Records") {
+            for record in records.value.iter() {
+                let Property::StructProperty(record) = record else {
+                    continue;
+                };
+                let var = find(&record.value, "ComponentKey").and_then(|key| match key {
+                    Property::StructProperty(key) => name(&key.value, "SCSVariableName"),
+                    _ => None,
+                });
+                let (Some(var), Some(export)) = (var, object(&record.value, "ComponentTemplate"))
+                else {
+                    continue;
+                };
+                overrides.push(template(var, None, export));
+            }
+        }
+    }
+    // native components are overridden through subobjects of the class default object
+    if let Some(default) = exports.iter().position(|ex| {
+        ex.get_base_export()
+            .object_name
+            .get_content(|name| name.starts_with("Default__"))
+    }) {
+        for (i, ex) in exports.iter().enumerate() {
+            let base = ex.get_base_export();
+            if base.outer_index.index as usize != default + 1 || actor::mesh_of(class, i).is_none()
+            {
+                continue;
+            }
+            overrides.push(template(base.object_name.get_owned_content(), None, i));
+        }
+    }
+    (parent, nodes, overrides)
+}
+
+/// every mesh an actor shows whether the map sets it or its class does
+pub fn pieces(map: &Asset, actor: &actor::Actor, registry: &Registry) -> Vec<actor::Piece> {
+    let mut pieces = actor.meshes(map);
+    pieces.extend(meshes(map, actor, registry));
+    pieces
+}
+
+/// gets the meshes an actor's class gives the components the map doesn't specify
+pub fn meshes(map: &Asset, actor: &actor::Actor, registry: &Registry) -> Vec<actor::Piece> {
+    let Some(templates) = class_path(map, actor).and_then(|path| registry.classes.get(&path))
+    else {
+        return Vec::new();
+    };
+    let name = |export: usize| {
+        map.asset_data.exports[export]
+            .get_base_export()
+            .object_name
+            .get_owned_content()
+    };
+    let root = name(actor.root());
+    // components with a mesh in the map have already been resolved
+    let resolved: Vec<_> = actor
+        .components(map)
+        .into_iter()
+        .filter(|&i| actor::mesh_of(map, i).is_some())
+        .map(name)
+        .collect();
+    templates
+        .iter()
+        .filter(|temp| !resolved.contains(&temp.name))
+        .filter_map(|temp| {
+            Some(actor::Piece {
+                mesh: temp.mesh.clone()?,
+                materials: temp.materials.clone(),
+                transform: relative(templates, temp, &root, 0),
+            })
+        })
+        .collect()
+}
+
+/// composes template transforms up to the root component which the actor's transform covers
+fn relative(templates: &[Template], temp: &Template, root: &str, depth: usize) -> Transform {
+    if temp.name == root {
+        return Transform::IDENTITY;
+    }
+    let transform = temp.transform.unwrap_or_default();
+    match temp
+        .parent
+        .as_ref()
+        .and_then(|parent| templates.iter().find(|temp| &temp.name == parent))
+        .filter(|_| depth < 64)
+    {
+        Some(parent) => relative(templates, parent, root, depth + 1).mul_transform(transform),
+        None => transform,
+    }
+}
@@ -0,0 +1,332 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+use unreal_asset::{cast, exports::ExportBaseTrait, types::PackageIndex};
+
+// copied actors are written as a package holding just the level and their exports
+// so pasting is a transplant from that package and works between separate instances
+// prefabs are the same thing kept in a file
+
+#[derive(Serialize, Deserialize)]
+struct Payload {
+    // packages can only be read with the version they were written with
+    version: String,
+    asset: String,
+    bulk: Option<String>,
+}
+
+/// writes a package to memory as it is without rebuilding its name map
+fn write(map: &mut Asset) -> Result<(Vec<u8>, Option<Vec<u8>>), unreal_asset::error::Error> {
+    let mut asset = Cursor::new(Vec::new());
+    let mut bulk = map
+        .asset_data
+        .use_event_driven_loader
+        .then(|| Cursor::new(Vec::new()));
+    map.write_data(&mut asset, bulk.as_mut())?;
+    Ok((asset.into_inner(), bulk.map(Cursor::into_inner)))
+}
+
+fn read(
+    (asset, bulk): (Vec<u8>, Option<Vec<u8>>),
+    version: unreal_asset::engine_version::EngineVersion,
+) -> Result<Asset, unreal_asset::error::Error> {
+    unreal_asset::Asset::new(
+        Wrapper::Bytes(Cursor::new(asset)),
+        bulk.map(|bulk| Wrapper::Bytes(Cursor::new(bulk))),
+        version,
+        None,
+    )
+}
+
+/// writes a package holding only the level and the given actors moved so origin is at zero
+pub fn package(
+    map: &mut Asset,
+    actors: &[&actor::Actor],
+    version: unreal_asset::engine_version::EngineVersion,
+    origin: Vec3,
+) -> Result<(Vec<u8>, Option<Vec<u8>>), unreal_asset::error::Error> {
+    // work on a copy so the open map is left alone
+    let mut copy = read(write(map)?, version)?;
+    if origin != Vec3::ZERO {
+        // attached actors follow their parent
+        for actor in actors.iter().filter(|actor| actor.parent(&copy).is_none()) {
+            actor.add_location(&mut copy, -origin)
+        }
+    }
+    let Some(mut level) = copy
+        .asset_data
+        .exports
+        .iter()
+        .find(|ex| cast!(Export, LevelExport, ex).is_some())
+        .cloned()
+    else {
+        return Err(unreal_asset::error::Error::no_data(
+            "the map has no level".into(),
+        ));
+    };
+    let level_ref = PackageIndex::new(1);
+    let mut exports = Vec::new();
+    let mut refs = Vec::new();
+    // references between the copied actors are kept
+    let group = actor::group_of(actors, &copy, 1);
+    for actor in actors {
+        let offset = exports.len() + 1;
+        let mut children = actor.get_group_exports(&copy, offset, &group);
+        let base = children[0].get_base_export_mut();
+        base.outer_index = level_ref;
+        if let Some(i) = base.create_before_create_dependencies.first_mut() {
+            *i = level_ref
+        }
+        refs.push(PackageIndex::new(offset as i32 + 1));
+        exports.append(&mut children);
+    }
+    if let Some(level) = cast!(Export, LevelExport, &mut level) {
+        level.actors.clone_from(&refs);
+        level
+            .get_base_export_mut()
+            .create_before_serialization_dependencies = refs;
+    }
+    exports.insert(0, level);
+    // every export has an entry when there's a depends map
+    if let Some(depends) = copy.asset_data.depends_map.as_mut() {
+        *depends = vec![Vec::new(); exports.len()]
+    }
+    copy.asset_data.exports = exports;
+    // only the names the kept exports use go along
+    copy.rebuild_name_map();
+    write(&mut copy)
+}
+
+/// writes actors as text for the clipboard or a prefab
+pub fn encode(
+    map: &mut Asset,
+    actors: &[&actor::Actor],
+    version: usize,
+    origin: Vec3,
+) -> Result<String, String> {
+    let (asset, bulk) =
+        package(map, actors, VERSIONS[version].0, origin).map_err(|e| e.to_string())?;
+    ron::to_string(&Payload {
+        version: VERSIONS[version].1.into(),
+        asset: hex::encode(asset),
+        bulk: bulk.map(hex::encode),
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// reads encoded actors back into a package to transplant from
+pub fn decode(text: &str) -> Result<Asset, String> {
+    let payload = ron::from_str::<Payload>(text).map_err(|e| e.to_string())?;
+    // transplanting converts from other versions where it can
+    let Some((version, _)) = VERSIONS.iter().find(|(_, name)| *name == payload.version) else {
+        return Err(format!(
+            "the actors are from an unknown version {}",
+            payload.version
+        ));
+    };
+    let asset = hex::decode(&payload.asset).map_err(|e| e.to_string())?;
+    let bulk = payload
+        .bulk
+        .map(hex::decode)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    read((asset, bulk), *version).map_err(|e| e.to_string())
+}
+
+#[test]
+fn payload() {
+    let mut map = regression::sample();
+    let (asset, bulk) = write(&mut map).unwrap();
+    let version = VERSIONS
+        .iter()
+        .position(|(ver, _)| *ver == unreal_asset::engine_version::EngineVersion::VER_UE4_27)
+        .unwrap();
+    let text = ron::to_string(&Payload {
+        version: VERSIONS[version].1.into(),
+        asset: hex::encode(&asset),
+        bulk: bulk.as_ref().map(hex::encode),
+    })
+    .unwrap();
+    let mut decoded = decode(&text).unwrap();
+    assert!(write(&mut decoded).unwrap() == (asset, bulk));
+    assert!(decode(&text.replace(VERSIONS[version].1, "0.1")).is_err());
+    // without a level there's nothing to hold the actors
+    assert!(package(&mut map, &[], VERSIONS[version].0, Vec3::ZERO).is_err());
+}
+
+/// transplants every actor in a package offset by a world space offset
+/// along with why any which couldn't be weren't
+/// and records them when they're from a file a patch can read again
+pub fn place(
+    commands: &mut Commands,
+    map: &mut Asset,
+    export_names: &mut Vec<String>,
+    import_names: &mut Vec<String>,
+    donor: &Asset,
+    from: Option<&std::path::Path>,
+    offset: Vec3,
+) -> (Vec<(Vec<actor::Piece>, actor::Actor)>, Vec<String>) {
+    let actors: Vec<_> = actor::get_actors(donor)
+        .into_iter()
+        .filter_map(|index| actor::Actor::new(donor, index).ok())
+        .map(|(_, actor)| actor)
+        .collect();
+    let actors: Vec<_> = actors.iter().collect();
+    let mut batch = Vec::new();
+    let mut problems = Vec::new();
+    for (actor, landed) in actors.iter().zip(actor::transplant_group(
+        &actors,
+        map,
+        donor,
+        export_names,
+        import_names,
+    )) {
+        let len = match landed {
+            Ok(len) => len,
+            Err(e) => {
+                problems.push(format!("{}: {}", actor.name, e.join(", ")));
+                continue;
+            }
+        };
+        let insert = PackageIndex::new(len as i32 + 1);
+        let (pieces, new) = match actor::Actor::new(map, insert) {
+            Ok(new) => new,
+            Err(e) => {
+                problems.push(format!("{}: {e}", actor.name));
+                continue;
+            }
+        };
+        if let Some(from) = from {
+            commands.trigger(triggers::Record(patch::Op::Transplant {
+                from: from.into(),
+                version: None,
+                actor: patch::name(donor, actor.export),
+                name: patch::name(map, len),
+            }));
+        }
+        if offset != Vec3::ZERO && new.parent(map).is_none() {
+            new.add_location(map, offset);
+            if from.is_some() {
+                commands.trigger(triggers::Record(patch::transform(
+                    map,
+                    &new,
+                    &new.transform(map),
+                )));
+            }
+        }
+        export_names[len] = new.name.clone();
+        batch.push((pieces, new));
+    }
+    (batch, problems)
+}
+
+pub fn copy(
+    _: Trigger<triggers::Copy>,
+    mut notif: EventWriter<Notif>,
+    mut clipboard: ResMut<bevy_egui::EguiClipboard>,
+    appdata: Res<AppData>,
+    mut map: NonSendMut<Map>,
+    selected: Query<&actor::Actor, With<actor::Selected>>,
+) {
+    let Some((map, ..)) = &mut map.0 else { return };
+    if selected.is_empty() {
+        notif.send(Notif {
+            message: "no actors to copy".into(),
+            kind: Warning,
+        });
+        return;
+    }
+    let actors: Vec<_> = selected.iter().collect();
+    match encode(map, &actors, appdata.version, Vec3::ZERO) {
+        Ok(payload) => {
+            clipboard.set_contents(&payload);
+            notif.send(Notif {
+                message: format!("{} actors copied", actors.len()),
+                kind: Success,
+            });
+        }
+        Err(e) => {
+            notif.send(Notif {
+                message: e,
+                kind: Error,
+            });
+        }
+    }
+}
+
+pub fn paste(
+    _: Trigger<triggers::Paste>,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    mut clipboard: ResMut<bevy_egui::EguiClipboard>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    mut map: NonSendMut<Map>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+    mut tabs: NonSendMut<tabs::Tabs>,
+) {
+    let Some((map, _, export_names, import_names)) = &mut map.0 else {
+        return;
+    };
+    let Some(text) = clipboard.get_contents() else {
+        notif.send(Notif {
+            message: "there are no actors on the clipboard".into(),
+            kind: Warning,
+        });
+        return;
+    };
+    let donor = match decode(&text) {
+        Ok(donor) => donor,
+        Err(e) => {
+            notif.send(Notif {
+                message: e,
+                kind: Error,
+            });
+            return;
+        }
+    };
+    for entity in selected.iter() {
+        outliner::deselect(&mut commands, &mut cubes, &consts, entity);
+    }
+    let (batch, problems) = place(
+        &mut commands,
+        map,
+        export_names,
+        import_names,
+        &donor,
+        // the clipboard can't be read back when replaying
+        None,
+        Vec3::ZERO,
+    );
+    for problem in problems {
+        notif.send(Notif {
+            message: format!("couldn't paste {problem}"),
+            kind: Error,
+        });
+    }
+    let pasted = batch.len();
+    dialog::spawn_new(
+        map,
+        batch,
+        &mut commands,
+        &mut registry,
+        &content,
+        &appdata,
+        &consts,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+    tabs.edit();
+    notif.send(Notif {
+        message: format!("{pasted} actors pasted"),
+        kind: Success,
+    });
+}
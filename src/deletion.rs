@@ -0,0 +1,211 @@
+use super::*;
+use unreal_asset::types::PackageIndex;
+
+// deleting an actor other things point at would leave them dangling
+// so those are shown first and the user decides what happens to them
+
+#[derive(PartialEq)]
+enum Choice {
+    Cancel,
+    Unlink,
+    // attached actors are deleted as well
+    Cascade,
+}
+
+#[derive(Default, Resource)]
+pub struct Deletion {
+    /// the selected actors waiting on a decision
+    actors: Vec<Entity>,
+    incoming: Vec<actor::Incoming>,
+    /// attached actors by export which can go with them
+    children: Vec<(usize, String)>,
+}
+
+impl Deletion {
+    pub fn ask(
+        &mut self,
+        actors: Vec<Entity>,
+        incoming: Vec<actor::Incoming>,
+        children: Vec<actor::Actor>,
+    ) {
+        self.actors = actors;
+        self.incoming = incoming;
+        self.children = children
+            .into_iter()
+            .map(|child| (child.export, child.name))
+            .collect();
+    }
+
+    fn cancel(&mut self) {
+        self.actors.clear();
+        self.incoming.clear();
+        self.children.clear();
+    }
+}
+
+/// deletes actors and nulls whatever pointed at them
+pub fn remove(
+    commands: &mut Commands,
+    notif: &mut EventWriter<Notif>,
+    map: &mut Asset,
+    actors: &[(Entity, &actor::Actor)],
+) {
+    let refs: Vec<_> = actors.iter().map(|(_, actor)| *actor).collect();
+    // actors left attached to nothing are kept where they were
+    let detached: Vec<_> = actor::attached(map, &refs)
+        .into_iter()
+        .map(|child| {
+            let world = child.transform(map);
+            (child, world)
+        })
+        .collect();
+    for (entity, actor) in actors {
+        commands.trigger(triggers::Record(patch::Op::Delete {
+            actor: patch::name(map, actor.export),
+        }));
+        notif.send(Notif {
+            message: format!("{} deleted", actor.name),
+            kind: Warning,
+        });
+        commands.entity(*entity).despawn_recursive()
+    }
+    actor::unlink(map, &refs);
+    for actor in refs {
+        actor.delete(map);
+    }
+    for (child, world) in detached {
+        let now = child.transform(map);
+        child.add_location(map, world.translation - now.translation);
+        child.combine_rotation(map, world.rotation * now.rotation.inverse());
+        // a zero scale can't be multiplied back out
+        if !now.scale.cmpeq(Vec3::ZERO).any() {
+            child.mul_scale(map, world.scale / now.scale);
+        }
+        commands.trigger(triggers::Record(patch::transform(map, &child, &world)));
+    }
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    mut deletion: ResMut<Deletion>,
+    mut map: NonSendMut<Map>,
+    actors: Query<(Entity, &actor::Actor)>,
+) {
+    if deletion.actors.is_empty() {
+        return;
+    }
+    // the map was closed or switched away from
+    let Some((map, ..)) = &mut map.0 else {
+        deletion.cancel();
+        return;
+    };
+    if deletion
+        .actors
+        .iter()
+        .any(|entity| actors.get(*entity).is_err())
+    {
+        deletion.cancel();
+        return;
+    }
+    let mut open = true;
+    let mut choice = None;
+    egui::Window::new("delete")
+        .open(&mut open)
+        .collapsible(false)
+        .default_width(350.0)
+        .show(ctx.ctx_mut(), |ui| {
+            let scripted = deletion
+                .incoming
+                .iter()
+                .filter(|incoming| incoming.script)
+                .count();
+            if scripted != 0 {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("{scripted} level blueprint links will break"),
+                );
+            }
+            if !deletion.incoming.is_empty() {
+                ui.label(format!(
+                    "{} references would be left pointing at nothing",
+                    deletion.incoming.len()
+                ));
+                egui::ScrollArea::vertical()
+                    .id_source("incoming")
+                    .max_height(200.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for incoming in deletion.incoming.iter() {
+                            let from = refs::name(map, PackageIndex::new(incoming.from as i32 + 1));
+                            let to = refs::name(map, PackageIndex::new(incoming.to as i32 + 1));
+                            ui.horizontal(|ui| {
+                                match incoming.script {
+                                    true => ui.colored_label(
+                                        ui.visuals().warn_fg_color,
+                                        format!("{from} → {to}"),
+                                    ),
+                                    false => ui.label(format!("{from} → {to}")),
+                                };
+                                ui.weak(&incoming.via);
+                            });
+                        }
+                    });
+            }
+            if !deletion.children.is_empty() {
+                ui.collapsing(
+                    format!("{} attached actors", deletion.children.len()),
+                    |ui| {
+                        for (_, name) in deletion.children.iter() {
+                            ui.label(name);
+                        }
+                    },
+                );
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("cancel").clicked() {
+                    choice = Some(Choice::Cancel)
+                }
+                if ui
+                    .button("null references")
+                    .on_hover_text("attached actors are detached where they are")
+                    .clicked()
+                {
+                    choice = Some(Choice::Unlink)
+                }
+                if ui
+                    .add_enabled(
+                        !deletion.children.is_empty(),
+                        egui::Button::new("delete attached too"),
+                    )
+                    .clicked()
+                {
+                    choice = Some(Choice::Cascade)
+                }
+            });
+        });
+    let mut doomed: Vec<_> = deletion
+        .actors
+        .iter()
+        .filter_map(|entity| actors.get(*entity).ok())
+        .collect();
+    match choice {
+        Some(Choice::Cancel) => deletion.cancel(),
+        Some(choice) => {
+            if choice == Choice::Cascade {
+                doomed.extend(actors.iter().filter(|(_, actor)| {
+                    deletion
+                        .children
+                        .iter()
+                        .any(|(export, _)| *export == actor.export)
+                }));
+            }
+            remove(&mut commands, &mut notif, map, &doomed);
+            deletion.cancel()
+        }
+        None if !open => deletion.cancel(),
+        None => (),
+    }
+}
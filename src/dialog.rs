@@ -4,18 +4,20 @@ use super::*;
 
 pub fn open(
     trigger: Trigger<triggers::Open>,
-    commands: Commands,
+    mut commands: Commands,
     actors: Query<Entity, With<actor::Actor>>,
     mut notif: EventWriter<Notif>,
     appdata: ResMut<AppData>,
     client: ResMut<Client>,
-    map: NonSendMut<Map>,
+    mut map: NonSendMut<Map>,
     registry: ResMut<Registry>,
     meshes: ResMut<Assets<Mesh>>,
     materials: ResMut<Assets<unlit::Unlit>>,
     images: ResMut<Assets<Image>>,
     content: Res<Content>,
     consts: Res<Constants>,
+    mut tabs: NonSendMut<tabs::Tabs>,
+    mut shown: tabs::Shown,
 ) {
     let Some(path) = trigger.event().0.clone().or_else(|| {
         rfd::FileDialog::new()
@@ -25,6 +27,10 @@ pub fn open(
     }) else {
         return;
     };
+    if let Some(i) = tabs.find(&path) {
+        commands.trigger(triggers::SwitchTab(i));
+        return;
+    }
     let asset = match asset::open(&path, appdata.version()) {
         Ok(asset) => asset,
         Err(e) => {
@@ -36,6 +42,12 @@ pub fn open(
         }
     };
     let name: Option<String> = path.to_str().map(|s| s.into());
+    tabs.open(
+        &mut map,
+        &mut shown,
+        name.as_deref().unwrap_or_default(),
+        Some(path.clone()),
+    );
     open_asset(
         name.as_deref(),
         Some(path),
@@ -52,25 +64,36 @@ pub fn open(
         images,
         content,
         consts,
+        &[],
     );
 }
 
 pub fn from_content(
     trigger: Trigger<triggers::FromContent>,
-    commands: Commands,
+    mut commands: Commands,
     actors: Query<Entity, With<actor::Actor>>,
     mut notif: EventWriter<Notif>,
     appdata: ResMut<AppData>,
     client: ResMut<Client>,
-    map: NonSendMut<Map>,
+    mut map: NonSendMut<Map>,
     registry: ResMut<Registry>,
     meshes: ResMut<Assets<Mesh>>,
     materials: ResMut<Assets<unlit::Unlit>>,
     images: ResMut<Assets<Image>>,
     content: Res<Content>,
     consts: Res<Constants>,
+    mut tabs: NonSendMut<tabs::Tabs>,
+    mut shown: tabs::Shown,
 ) {
     let name = &trigger.event().0;
+    let file = match &trigger.event().1 {
+        GamePath::Loose(path) => path.clone(),
+        GamePath::Packed(path) => std::path::PathBuf::from(path),
+    };
+    if let Some(i) = tabs.find(&file) {
+        commands.trigger(triggers::SwitchTab(i));
+        return;
+    }
     match &trigger.event().1 {
         GamePath::Loose(path) => {
             let asset = match asset::open(path, appdata.version()) {
@@ -83,6 +106,7 @@ pub fn from_content(
                     return;
                 }
             };
+            tabs.open(&mut map, &mut shown, name, Some(file));
             open_asset(
                 Some(name),
                 None,
@@ -99,6 +123,7 @@ pub fn from_content(
                 images,
                 content,
                 consts,
+                &[],
             );
         }
         GamePath::Packed(path) => {
@@ -125,6 +150,7 @@ pub fn from_content(
             }) else {
                 return;
             };
+            tabs.open(&mut map, &mut shown, name, Some(file));
             open_asset(
                 Some(name),
                 None,
@@ -141,6 +167,7 @@ pub fn from_content(
                 images,
                 content,
                 consts,
+                &[],
             );
         }
     }
@@ -162,6 +189,8 @@ fn open_asset(
     mut images: ResMut<Assets<Image>>,
     content: Res<Content>,
     consts: Res<Constants>,
+    // exports to select once spawned
+    selected: &[usize],
 ) {
     use discord_rich_presence::DiscordIpc;
     if let (Some(client), Some(name)) = (client.0.as_mut(), name) {
@@ -174,11 +203,7 @@ fn open_asset(
     for actor in actors.iter() {
         commands.entity(actor).despawn_recursive();
     }
-    let cache = config()
-        .filter(|_| appdata.cache)
-        .map(|path| path.join("cache"));
-    let version = appdata.version();
-    let mut batch = std::collections::BTreeMap::<_, Vec<_>>::new();
+    let mut batch = Vec::new();
     let mut export_names: Vec<_> = asset
         .asset_data
         .exports
@@ -186,7 +211,7 @@ fn open_asset(
         .map(|ex| ex.get_base_export().object_name.get_owned_content())
         .collect();
     for i in actor::get_actors(&asset) {
-        let (path, actor) = match actor::Actor::new(&asset, i) {
+        let (pieces, actor) = match actor::Actor::new(&asset, i) {
             Ok(actor) => actor,
             Err(e) => {
                 notif.send(Notif {
@@ -197,14 +222,91 @@ fn open_asset(
             }
         };
         export_names[i.index as usize - 1] = actor.name.clone();
-        match batch.get_mut(&path) {
-            Some(vec) => vec.push(actor),
-            None => {
-                batch.insert(path, vec![actor]);
+        batch.push((pieces, actor));
+    }
+    load(
+        &asset,
+        &mut batch,
+        &mut registry,
+        &content,
+        &appdata,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+    for (pieces, actor) in batch {
+        let picked = selected.contains(&actor.export);
+        actor::spawn(
+            &mut commands,
+            &registry,
+            &consts,
+            actor.transform(&asset),
+            pieces,
+            actor,
+            picked,
+        );
+    }
+    let import_names = asset
+        .imports
+        .iter()
+        .map(|ex| ex.object_name.get_owned_content())
+        .collect();
+    map.0 = Some((asset, path, export_names, import_names));
+    notif.send(Notif {
+        message: "map opened".into(),
+        kind: Success,
+    });
+}
+
+/// loads the meshes and materials of actors which haven't been tried yet
+pub fn load(
+    asset: &super::Asset,
+    batch: &mut [(Vec<actor::Piece>, actor::Actor)],
+    registry: &mut Registry,
+    content: &Content,
+    appdata: &AppData,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<unlit::Unlit>,
+    images: &mut Assets<Image>,
+    notif: &mut EventWriter<Notif>,
+) {
+    let cache = config()
+        .filter(|_| appdata.cache)
+        .map(|path| path.join("cache"));
+    let version = appdata.version();
+    // blueprint actors often only get their meshes from their class
+    let classes: std::collections::BTreeSet<_> = batch
+        .iter()
+        .filter_map(|(_, actor)| blueprint::class_path(asset, actor))
+        .filter(|path| !registry.classes.contains_key(path))
+        .collect();
+    std::thread::scope(|s| {
+        let threads: Vec<_> = classes
+            .into_iter()
+            .map(|path| {
+                s.spawn(|| {
+                    // capture path
+                    let path = path;
+                    let templates = blueprint::templates(content, cache.as_deref(), &path, version);
+                    (path, templates)
+                })
+            })
+            .collect();
+        for thread in threads {
+            if let Ok((path, templates)) = thread.join() {
+                registry.classes.insert(path, templates);
             }
         }
+    });
+    for (pieces, actor) in batch.iter_mut() {
+        *pieces = blueprint::pieces(asset, actor, registry);
     }
-    let keys = batch.keys().flatten().cloned().collect::<Vec<_>>();
+    let keys = batch
+        .iter()
+        .flat_map(|(pieces, _)| pieces.iter().map(|piece| piece.mesh.clone()))
+        .filter(|path| !registry.meshes.contains_key(path))
+        .collect::<std::collections::BTreeSet<_>>();
     std::thread::scope(|s| {
         let threads: Vec<_> = keys
             .into_iter()
@@ -212,23 +314,12 @@ fn open_asset(
                 s.spawn(|| {
                     // capture path
                     let path = path;
-                    match asset::get(&content, cache.as_deref(), &path, version, |asset, _| {
+                    match asset::get(content, cache.as_deref(), &path, version, |asset, _| {
                         Ok(extras::get_mesh_info(asset)?)
                     }) {
-                        Some((positions, indices, uvs, mats, _mat_data)) => Ok((
-                            path,
-                            Mesh::new(
-                                bevy::render::render_resource::PrimitiveTopology::TriangleList,
-                                default(),
-                            )
-                            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
-                            .with_inserted_attribute(
-                                Mesh::ATTRIBUTE_UV_0,
-                                uvs.into_iter().map(|uv| uv[0]).collect::<Vec<_>>(),
-                            )
-                            .with_inserted_indices(bevy::render::mesh::Indices::U32(indices)),
-                            mats,
-                        )),
+                        Some((positions, indices, uvs, mats, mat_data)) => {
+                            Ok((path, split(positions, indices, uvs, mat_data), mats))
+                        }
                         None => Err(path),
                     }
                 })
@@ -236,152 +327,173 @@ fn open_asset(
             .collect();
         for thread in threads {
             match thread.join() {
-                Ok(Ok((path, mesh, mats))) => {
-                    // hard to multithread material loading since the material might not parse
-                    let mut tex = None;
-                    if appdata.textures {
-                        'outer: for mat in mats {
-                            if registry.mats.contains_key(&mat) {
-                                break;
-                            }
-                            let Some(paths) =
-                                asset::get(&content, cache.as_deref(), &mat, version, |mat, _| {
-                                    Ok(extras::get_tex_paths(mat))
-                                })
-                            else {
-                                continue;
-                            };
-                            for path in paths {
-                                if let Some((false, width, height, data)) = asset::get(
-                                    &content,
-                                    cache.as_deref(),
-                                    &path,
-                                    version,
-                                    |tex, bulk| Ok(extras::get_tex_info(tex, bulk)?),
-                                ) {
-                                    tex = Some((path, Image {
-                                        data,
-                                        texture_descriptor: bevy::render::render_resource::TextureDescriptor {
-                                            label: None,
-                                            size: bevy::render::render_resource::Extent3d {
-                                                width,
-                                                height,
-                                                depth_or_array_layers: 1,
-                                            },
-                                            mip_level_count: 1,
-                                            sample_count: 1,
-                                            dimension: bevy::render::render_resource::TextureDimension::D2,
-                                            format: bevy::render::render_resource::TextureFormat::Bgra8UnormSrgb,
-                                            usage:
-                                                bevy::render::render_resource::TextureUsages::TEXTURE_BINDING,
-                                            view_formats: &[
-                                                bevy::render::render_resource::TextureFormat::Bgra8UnormSrgb,
-                                            ],
-                                        },
-                                        sampler: bevy::render::texture::ImageSampler::Descriptor(
-                                            bevy::render::texture::ImageSamplerDescriptor {
-                                                address_mode_u: bevy::render::texture::ImageAddressMode::Repeat,
-                                                address_mode_v: bevy::render::texture::ImageAddressMode::Repeat,
-                                                address_mode_w: bevy::render::texture::ImageAddressMode::Repeat,
-                                                ..default()
-                                            },
-                                        ),
-                                        ..default()
-                                    }));
-                                    break 'outer;
-                                }
-                            }
-                        }
-                    }
+                Ok(Ok((path, sections, mats))) => {
                     registry.meshes.insert(
-                        path.clone(),
-                        (meshes.add(mesh), tex.as_ref().map(|(path, _)| path.clone())),
+                        path,
+                        (
+                            sections
+                                .into_iter()
+                                .map(|(mesh, slot)| (meshes.add(mesh), slot))
+                                .collect(),
+                            mats,
+                        ),
                     );
-                    if let Some((path, tex)) = tex {
-                        registry.mats.insert(
-                            path,
-                            materials.add(unlit::Unlit {
-                                texture: images.add(tex),
-                            }),
-                        );
-                    }
                 }
                 Ok(Err(path)) => {
                     notif.send(Notif {
                         message: format!("couldn't find the mesh at {path}"),
                         kind: egui_notify::ToastLevel::Warning,
                     });
-                    batch.remove(&Some(path));
                 }
                 Err(_) => continue,
             }
         }
     });
-    for (path, actors) in batch {
-        match path {
-            Some(ref path) => {
-                let (mesh, material) = &registry.meshes[path];
-                for actor in actors {
-                    commands.spawn((
-                        MaterialMeshBundle {
-                            mesh: mesh.clone_weak(),
-                            material: material
-                                .as_ref()
-                                .map(|mat| registry.mats[mat].clone_weak())
-                                .unwrap_or(consts.grid.clone_weak()),
-                            transform: actor.transform(&asset),
-                            ..default()
-                        },
-                        bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
-                        actor,
-                    ));
-                }
-            }
-            None => {
-                for actor in actors {
-                    commands
-                        .spawn((
-                            MaterialMeshBundle {
-                                mesh: consts.cube.clone_weak(),
-                                material: consts.unselected.clone_weak(),
-                                transform: actor.transform(&asset),
-                                ..default()
-                            },
-                            bevy::pbr::wireframe::NoWireframe,
-                            actor,
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn((
-                                consts.bounds.clone_weak(),
-                                SpatialBundle {
-                                    visibility: Visibility::Hidden,
-                                    ..default()
-                                },
-                                bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
-                            ));
-                        });
-                }
-                continue;
-            }
-        }
+    if appdata.textures {
+        // overrides take the place of the mesh's own material in their slot
+        let mats: std::collections::BTreeSet<_> = batch
+            .iter()
+            .flat_map(|(pieces, _)| pieces.iter())
+            .flat_map(|piece| {
+                let defaults = registry
+                    .meshes
+                    .get(&piece.mesh)
+                    .map(|(_, mats)| mats.as_slice())
+                    .unwrap_or_default();
+                (0..defaults.len().max(piece.materials.len())).filter_map(|slot| {
+                    piece
+                        .materials
+                        .get(slot)
+                        .cloned()
+                        .flatten()
+                        .or_else(|| defaults.get(slot).cloned())
+                })
+            })
+            .collect();
+        load_materials(
+            mats,
+            registry,
+            content,
+            cache.as_deref(),
+            version,
+            materials,
+            images,
+        );
     }
-    let import_names = asset
-        .imports
+}
+
+/// loads what newly added actors need then spawns them selected
+pub fn spawn_new(
+    map: &super::Asset,
+    mut batch: Vec<(Vec<actor::Piece>, actor::Actor)>,
+    commands: &mut Commands,
+    registry: &mut Registry,
+    content: &Content,
+    appdata: &AppData,
+    consts: &Constants,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<unlit::Unlit>,
+    images: &mut Assets<Image>,
+    notif: &mut EventWriter<Notif>,
+) {
+    load(
+        map, &mut batch, registry, content, appdata, meshes, materials, images, notif,
+    );
+    for (pieces, actor) in batch {
+        actor::spawn(
+            commands,
+            registry,
+            consts,
+            actor.transform(map),
+            pieces,
+            actor,
+            true,
+        );
+    }
+}
+
+/// splits a mesh by material section so each slot can be textured separately
+fn split(
+    positions: Vec<Vec3>,
+    indices: Vec<u32>,
+    uvs: Vec<Vec<Vec2>>,
+    mut sections: Vec<(u32, u32)>,
+) -> Vec<(Mesh, usize)> {
+    let uvs: Vec<_> = uvs.into_iter().map(|uv| uv[0]).collect();
+    sections.sort_by_key(|&(_, first)| first);
+    if sections.is_empty() {
+        sections.push((0, 0))
+    }
+    // sections are contiguous so each ends where the next starts
+    let ends: Vec<_> = sections
         .iter()
-        .map(|ex| ex.object_name.get_owned_content())
+        .skip(1)
+        .map(|&(_, first)| first as usize)
+        .chain(std::iter::once(indices.len()))
+        .map(|end| end.min(indices.len()))
         .collect();
-    map.0 = Some((asset, path, export_names, import_names));
-    notif.send(Notif {
-        message: "map opened".into(),
-        kind: Success,
-    });
+    sections
+        .iter()
+        .zip(ends)
+        .map(|(&(slot, first), end)| {
+            // only keep the vertices the section uses
+            let mut remap = std::collections::HashMap::new();
+            let (mut pos, mut uv) = (Vec::new(), Vec::new());
+            let local: Vec<u32> = indices[(first as usize).min(end)..end]
+                .iter()
+                .map(|&i| {
+                    *remap.entry(i).or_insert_with(|| {
+                        pos.push(positions[i as usize]);
+                        uv.push(uvs[i as usize]);
+                        pos.len() as u32 - 1
+                    })
+                })
+                .collect();
+            (
+                Mesh::new(
+                    bevy::render::render_resource::PrimitiveTopology::TriangleList,
+                    default(),
+                )
+                .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, pos)
+                .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uv)
+                .with_inserted_indices(bevy::render::mesh::Indices::U32(local)),
+                slot as usize,
+            )
+        })
+        .collect()
+}
+
+/// loads the textures of materials which haven't been tried yet
+pub fn load_materials(
+    mats: impl IntoIterator<Item = String>,
+    registry: &mut Registry,
+    content: &Content,
+    cache: Option<&std::path::Path>,
+    version: unreal_asset::engine_version::EngineVersion,
+    materials: &mut Assets<unlit::Unlit>,
+    images: &mut Assets<Image>,
+) {
+    // hard to multithread material loading since the material might not parse
+    for mat in mats {
+        if registry.mats.contains_key(&mat) {
+            continue;
+        }
+        let unlit = asset::material(content, cache, &mat, version).map(|tex| {
+            materials.add(unlit::Unlit {
+                texture: images.add(tex),
+            })
+        });
+        registry.mats.insert(mat, unlit);
+    }
 }
 
 pub fn save_as(
     trigger: Trigger<triggers::SaveAs>,
     mut notif: EventWriter<Notif>,
     appdata: Res<AppData>,
+    mut validator: ResMut<validate::Validator>,
     mut map: NonSendMut<Map>,
+    mut tabs: NonSendMut<tabs::Tabs>,
 ) {
     let Some((map, path, ..)) = &mut map.0 else {
         notif.send(Notif {
@@ -390,18 +502,47 @@ pub fn save_as(
         });
         return;
     };
+    let findings = validate::check(map);
+    if !std::mem::take(&mut validator.force)
+        && findings
+            .iter()
+            .any(|finding| finding.severity == validate::Severity::Fatal)
+    {
+        validator.report(findings);
+        validator.blocked = Some(trigger.event().0);
+        notif.send(Notif {
+            message: format!(
+                "didn't save because of {} fatal problems",
+                validator.fatal()
+            ),
+            kind: Error,
+        });
+        return;
+    }
     if trigger.event().0 || path.is_none() {
         if let Some(new) = rfd::FileDialog::new()
             .set_title("save map as")
             .add_filter("maps", &["umap"])
             .save_file()
         {
+            // another tab saving over the same file would undo this one
+            if let Some(i) = tabs.find(&new).filter(|&i| i != tabs.active) {
+                notif.send(Notif {
+                    message: format!(
+                        "{} is open in another tab so close it first",
+                        tabs.tabs[i].name
+                    ),
+                    kind: Error,
+                });
+                return;
+            }
             *path = Some(new);
         }
     }
     let Some(path) = path else { return };
-    match asset::save(map, path) {
+    match asset::save(map, &*path) {
         Ok(_) => {
+            tabs.saved(path);
             // literally no idea why std::process::Command doesn't work
             #[cfg(target_os = "windows")]
             const PATH: &str = "./script.bat";
@@ -483,19 +624,11 @@ pub fn transplant_from(
     else {
         return;
     };
-    match asset::open(path, appdata.version()) {
+    match asset::open(&path, appdata.version()) {
         Ok(donor) => {
-            // no need for verbose warnings here
-            let actors: Vec<_> = actor::get_actors(&donor)
-                .into_iter()
-                .filter_map(|index| {
-                    actor::Actor::new(&donor, index)
-                        .ok()
-                        .map(|(_, actor)| actor)
-                })
-                .collect();
+            let actors = donor_actors(&donor);
             let selected = Vec::with_capacity(actors.len());
-            transplant.0 = Some((donor, actors, selected));
+            transplant.0 = Some((donor, path, actors, selected, appdata.version));
         }
         Err(e) => {
             notif.send(Notif {
@@ -506,6 +639,133 @@ pub fn transplant_from(
     }
 }
 
+fn donor_actors(donor: &super::Asset) -> Vec<actor::Actor> {
+    // no need for verbose warnings here
+    actor::get_actors(donor)
+        .into_iter()
+        .filter_map(|index| actor::Actor::new(donor, index).ok().map(|(_, actor)| actor))
+        .collect()
+}
+
+/// reads the donor again with another version so actors can come from other games
+pub fn donor_version(
+    trigger: Trigger<triggers::DonorVersion>,
+    mut notif: EventWriter<Notif>,
+    mut transplant: NonSendMut<Transplant>,
+) {
+    let Some((donor, path, actors, selected, version)) = &mut transplant.0 else {
+        return;
+    };
+    let new = trigger.event().0;
+    match asset::open(path, VERSIONS[new].0) {
+        Ok(reread) => {
+            *actors = donor_actors(&reread);
+            *donor = reread;
+            selected.clear();
+            *version = new;
+        }
+        Err(e) => {
+            notif.send(Notif {
+                message: format!("couldn't read the donor as {}: {e}", VERSIONS[new].1),
+                kind: Error,
+            });
+        }
+    }
+}
+
+/// transplants the actors picked in the transplant window
+pub fn transplant(
+    _: Trigger<triggers::TransplantSelected>,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    mut map: NonSendMut<Map>,
+    mut transplant: NonSendMut<Transplant>,
+    camera: Query<(
+        &bevy_mod_raycast::deferred::RaycastSource<()>,
+        &smooth_bevy_cameras::LookTransform,
+    )>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+) {
+    let (Some((donor, from, others, picked, version)), Some((map, _, export_names, import_names))) =
+        (transplant.0.take(), &mut map.0)
+    else {
+        return;
+    };
+    for entity in selected.iter() {
+        outliner::deselect(&mut commands, &mut cubes, &consts, entity);
+    }
+    let actors: Vec<_> = picked.iter().map(|i| &others[*i]).collect();
+    let landed = actor::transplant_group(&actors, map, &donor, export_names, import_names);
+    let mut batch = Vec::with_capacity(actors.len());
+    for (actor, landed) in actors.into_iter().zip(landed) {
+        let len = match landed {
+            Ok(len) => len,
+            Err(problems) => {
+                notif.send(Notif {
+                    message: format!(
+                        "couldn't transplant {}:\n{}",
+                        actor.name,
+                        problems.join("\n")
+                    ),
+                    kind: Error,
+                });
+                continue;
+            }
+        };
+        let insert = unreal_asset::types::PackageIndex::new(len as i32 + 1);
+        notif.send(Notif {
+            message: format!("transplanted {}", actor.name),
+            kind: Success,
+        });
+        commands.trigger(triggers::Record(patch::Op::Transplant {
+            from: from.clone(),
+            version: Some(VERSIONS[version].1.into()),
+            actor: patch::name(&donor, actor.export),
+            name: patch::name(map, len),
+        }));
+        let (pieces, new) = match actor::Actor::new(map, insert) {
+            Ok(new) => new,
+            Err(e) => {
+                notif.send(Notif {
+                    message: format!("couldn't transplant {}: {e}", actor.name),
+                    kind: Error,
+                });
+                continue;
+            }
+        };
+        export_names[len] = new.name.clone();
+        batch.push((pieces, new));
+    }
+    if let Some(target) = camera
+        .get_single()
+        .ok()
+        .and_then(|(source, look)| action::target(appdata.placement, source, look))
+    {
+        action::relocate(&mut commands, map, &batch, target)
+    }
+    spawn_new(
+        map,
+        batch,
+        &mut commands,
+        &mut registry,
+        &content,
+        &appdata,
+        &consts,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+}
+
 pub fn transplant_into(
     _: Trigger<triggers::TransplantInto>,
     commands: Commands,
@@ -521,6 +781,8 @@ pub fn transplant_into(
     images: ResMut<Assets<Image>>,
     content: Res<Content>,
     consts: Res<Constants>,
+    mut tabs: NonSendMut<tabs::Tabs>,
+    mut shown: tabs::Shown,
 ) {
     let Some((donor, ..)) = &mut map.0 else {
         notif.send(Notif {
@@ -543,6 +805,17 @@ pub fn transplant_into(
     else {
         return;
     };
+    // transplanting into a second copy of an open map would fight its tab over saves
+    if let Some(i) = tabs.find(&path) {
+        notif.send(Notif {
+            message: format!(
+                "{} is already open so drag the actors onto its tab instead",
+                tabs.tabs[i].name
+            ),
+            kind: Error,
+        });
+        return;
+    }
     let mut recipient = match asset::open(&path, appdata.version()) {
         Ok(recipient) => recipient,
         Err(e) => {
@@ -553,13 +826,43 @@ pub fn transplant_into(
             return;
         }
     };
-    for actor in selected.iter() {
-        actor.transplant(&mut recipient, donor, &mut vec![], &mut vec![]);
-        notif.send(Notif {
-            message: format!("transplanted {}", actor.name),
-            kind: Success,
-        });
+    let picked: Vec<_> = selected.iter().collect();
+    let mut transplanted = Vec::with_capacity(picked.len());
+    for (actor, landed) in picked.iter().zip(actor::transplant_group(
+        &picked,
+        &mut recipient,
+        donor,
+        &mut vec![],
+        &mut vec![],
+    )) {
+        match landed {
+            Ok(len) => {
+                transplanted.push(len);
+                notif.send(Notif {
+                    message: format!("transplanted {}", actor.name),
+                    kind: Success,
+                });
+            }
+            Err(problems) => {
+                notif.send(Notif {
+                    message: format!(
+                        "couldn't transplant {}:\n{}",
+                        actor.name,
+                        problems.join("\n")
+                    ),
+                    kind: Error,
+                });
+            }
+        }
     }
+    // the donor stays open in its own tab
+    tabs.open(
+        &mut map,
+        &mut shown,
+        path.to_str().unwrap_or_default(),
+        Some(path.clone()),
+    );
+    tabs.edit();
     open_asset(
         None,
         Some(path),
@@ -576,5 +879,130 @@ pub fn transplant_into(
         images,
         content,
         consts,
+        &transplanted,
+    );
+}
+
+pub fn apply_patch(
+    _: Trigger<triggers::ApplyPatch>,
+    commands: Commands,
+    mut notif: EventWriter<Notif>,
+    appdata: ResMut<AppData>,
+    mut map: NonSendMut<Map>,
+    actors: Query<Entity, With<actor::Actor>>,
+    client: ResMut<Client>,
+    registry: ResMut<Registry>,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<unlit::Unlit>>,
+    images: ResMut<Assets<Image>>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut tabs: NonSendMut<tabs::Tabs>,
+) {
+    if map.0.is_none() {
+        notif.send(Notif {
+            message: "no map to apply the patch to".into(),
+            kind: Error,
+        });
+        return;
+    }
+    let Some(file) = rfd::FileDialog::new()
+        .set_title("apply patch")
+        .add_filter("ron", &["ron"])
+        .pick_file()
+    else {
+        return;
+    };
+    let patch = match std::fs::read_to_string(file)
+        .map_err(|e| e.to_string())
+        .and_then(|text| ron::from_str::<patch::Patch>(&text).map_err(|e| e.to_string()))
+    {
+        Ok(patch) => patch,
+        Err(e) => {
+            notif.send(Notif {
+                message: e,
+                kind: Error,
+            });
+            return;
+        }
+    };
+    let Some((mut asset, path, ..)) = map.0.take() else {
+        return;
+    };
+    let problems = patch::replay(&mut asset, &patch, appdata.version());
+    tabs.edit();
+    notif.send(Notif {
+        message: format!(
+            "{} of {} operations applied",
+            patch.ops.len() - problems.len(),
+            patch.ops.len()
+        ),
+        kind: match problems.is_empty() {
+            true => Success,
+            false => Warning,
+        },
+    });
+    for problem in problems {
+        notif.send(Notif {
+            message: problem,
+            kind: Warning,
+        });
+    }
+    // respawn everything since actors could have been added anywhere
+    open_asset(
+        None,
+        path,
+        asset,
+        commands,
+        actors,
+        notif,
+        appdata,
+        client,
+        map,
+        registry,
+        meshes,
+        materials,
+        images,
+        content,
+        consts,
+        &[],
+    );
+}
+
+pub fn switch_tab(
+    trigger: Trigger<triggers::SwitchTab>,
+    commands: Commands,
+    actors: Query<Entity, With<actor::Actor>>,
+    notif: EventWriter<Notif>,
+    appdata: ResMut<AppData>,
+    client: ResMut<Client>,
+    mut map: NonSendMut<Map>,
+    registry: ResMut<Registry>,
+    meshes: ResMut<Assets<Mesh>>,
+    materials: ResMut<Assets<unlit::Unlit>>,
+    images: ResMut<Assets<Image>>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut tabs: NonSendMut<tabs::Tabs>,
+    mut shown: tabs::Shown,
+) {
+    let i = trigger.event().0;
+    if i == tabs.active || i >= tabs.tabs.len() {
+        return;
+    }
+    tabs.stash(&mut map, &mut shown);
+    tabs.active = i;
+    let Some(mut stash) = tabs.tabs[i].stash.take() else {
+        return;
+    };
+    shown.restore(&mut stash);
+    let tabs::Stash {
+        map: (asset, path, ..),
+        selected,
+        ..
+    } = stash;
+    open_asset(
+        None, path, asset, commands, actors, notif, appdata, client, map, registry, meshes,
+        materials, images, content, consts, &selected,
     );
 }
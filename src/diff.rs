@@ -0,0 +1,632 @@
+use super::*;
+use std::collections::BTreeMap;
+use unreal_asset::{
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::{int_property::BytePropertyValue, Property, PropertyDataTrait},
+    types::PackageIndex,
+};
+
+/// a property whose value differs with none meaning it isn't there
+pub struct Change {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+pub struct Entry {
+    pub name: String,
+    pub label: String,
+    pub class: String,
+    // the actor's export in each map
+    pub before: Option<usize>,
+    pub after: Option<usize>,
+    pub transform: Option<(Transform, Transform)>,
+    pub changes: Vec<Change>,
+}
+
+impl Entry {
+    fn sign(&self) -> &'static str {
+        match (self.before, self.after) {
+            (None, _) => "+",
+            (_, None) => "-",
+            _ => "~",
+        }
+    }
+}
+
+/// formats a transform in unreal units
+fn describe(transform: &Transform) -> String {
+    let pos = transform.translation * 100.0;
+    let (x, y, z) = transform.rotation.to_euler(EulerRot::XYZ);
+    format!(
+        "location ({:.2}, {:.2}, {:.2}) rotation ({:.2}, {:.2}, {:.2}) scale ({:.2}, {:.2}, {:.2})",
+        pos.x,
+        pos.z,
+        pos.y,
+        x.to_degrees(),
+        -y.to_degrees(),
+        z.to_degrees(),
+        transform.scale.x,
+        transform.scale.z,
+        transform.scale.y
+    )
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} {} [{}]", self.sign(), self.label, self.class)?;
+        if let Some((before, after)) = &self.transform {
+            writeln!(f, "    transform: {}", describe(before))?;
+            writeln!(f, "            -> {}", describe(after))?;
+        }
+        for change in self.changes.iter() {
+            writeln!(
+                f,
+                "    {}: {} -> {}",
+                change.path,
+                change.before.as_deref().unwrap_or("none"),
+                change.after.as_deref().unwrap_or("none")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn object(map: &Asset, index: PackageIndex) -> String {
+    match index.index {
+        0 => "null".into(),
+        i if i > 0 => map
+            .get_export(index)
+            .map(|ex| ex.get_base_export().object_name.get_owned_content())
+            .unwrap_or_default(),
+        _ => map
+            .get_import(index)
+            .map(|imp| imp.object_name.get_owned_content())
+            .unwrap_or_default(),
+    }
+}
+
+pub fn key(prop: &Property) -> String {
+    let name = prop.get_name().get_owned_content();
+    match prop.get_duplication_index() {
+        0 => name,
+        i => format!("{name}[{i}]"),
+    }
+}
+
+/// flattens a property into paths and comparable values
+fn flatten(map: &Asset, prop: &Property, path: String, out: &mut BTreeMap<String, String>) {
+    let value = match prop {
+        Property::StructProperty(struc) => {
+            match struc.value.as_slice() {
+                // vectors and colours are wrapped in a struct of the same name
+                [inner] if inner.get_name() == struc.name => flatten(map, inner, path, out),
+                fields => {
+                    for field in fields {
+                        flatten(map, field, format!("{path}.{}", key(field)), out)
+                    }
+                }
+            }
+            return;
+        }
+        Property::ArrayProperty(arr) => {
+            out.insert(format!("{path}.len"), arr.value.len().to_string());
+            for (i, entry) in arr.value.iter().enumerate() {
+                flatten(map, entry, format!("{path}[{i}]"), out)
+            }
+            return;
+        }
+        Property::MapProperty(pairs) => {
+            for (i, (key, val)) in pairs.value.iter().enumerate() {
+                flatten(map, key, format!("{path}[{i}].key"), out);
+                flatten(map, val, format!("{path}[{i}].value"), out)
+            }
+            return;
+        }
+        Property::SetProperty(set) => {
+            for (i, entry) in set.value.value.iter().enumerate() {
+                flatten(map, entry, format!("{path}{{{i}}}"), out)
+            }
+            return;
+        }
+        Property::VectorProperty(vec) => {
+            format!("({}, {}, {})", vec.value.x.0, vec.value.y.0, vec.value.z.0)
+        }
+        Property::RotatorProperty(rot) => {
+            format!("({}, {}, {})", rot.value.x.0, rot.value.y.0, rot.value.z.0)
+        }
+        Property::ColorProperty(col) => format!(
+            "({}, {}, {}, {})",
+            col.color.r, col.color.g, col.color.b, col.color.a
+        ),
+        Property::LinearColorProperty(col) => format!(
+            "({}, {}, {}, {})",
+            col.color.r.0, col.color.g.0, col.color.b.0, col.color.a.0
+        ),
+        Property::BoolProperty(bool) => bool.value.to_string(),
+        Property::FloatProperty(float) => float.value.0.to_string(),
+        Property::DoubleProperty(double) => double.value.0.to_string(),
+        Property::IntProperty(int) => int.value.to_string(),
+        Property::Int8Property(int) => int.value.to_string(),
+        Property::Int16Property(int) => int.value.to_string(),
+        Property::Int64Property(int) => int.value.to_string(),
+        Property::UInt16Property(int) => int.value.to_string(),
+        Property::UInt32Property(int) => int.value.to_string(),
+        Property::UInt64Property(int) => int.value.to_string(),
+        Property::ByteProperty(byte) => match &byte.value {
+            BytePropertyValue::Byte(byte) => byte.to_string(),
+            BytePropertyValue::FName(name) => name.get_owned_content(),
+        },
+        Property::EnumProperty(enm) => enm
+            .value
+            .as_ref()
+            .map(|val| val.get_owned_content())
+            .unwrap_or_default(),
+        Property::NameProperty(name) => name.value.get_owned_content(),
+        Property::StrProperty(str) => format!("{:?}", str.value.as_deref().unwrap_or_default()),
+        // indices differ between packages so compare what they point to
+        Property::ObjectProperty(obj) => object(map, obj.value),
+        prop => format!("{prop:?}"),
+    };
+    out.insert(path, value);
+}
+
+/// the flattened properties of an actor and its components by object name
+fn properties(map: &Asset, actor: &actor::Actor) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+    let mut exports = actor.components(map);
+    exports.insert(0, actor.export);
+    for export in exports {
+        let Some(norm) = map.asset_data.exports[export].get_normal_export() else {
+            continue;
+        };
+        // the actor's own properties aren't prefixed
+        let prefix = match export == actor.export {
+            true => String::new(),
+            false => norm.base_export.object_name.get_owned_content() + ".",
+        };
+        for prop in norm.properties.iter() {
+            flatten(map, prop, format!("{prefix}{}", key(prop)), &mut out)
+        }
+    }
+    out
+}
+
+fn actors(map: &Asset) -> BTreeMap<String, actor::Actor> {
+    actor::get_actors(map)
+        .into_iter()
+        .filter_map(|index| actor::Actor::new(map, index).ok())
+        .map(|(_, actor)| {
+            (
+                actor::full_name(
+                    &map.asset_data.exports[actor.export]
+                        .get_base_export()
+                        .object_name,
+                ),
+                actor,
+            )
+        })
+        .collect()
+}
+
+/// the meshes an actor's class gives it which aren't in its properties
+fn class_meshes(map: &Asset, actor: &actor::Actor, registry: Option<&Registry>) -> Option<String> {
+    let meshes: Vec<_> = registry
+        .map(|registry| blueprint::meshes(map, actor, registry))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|piece| piece.mesh)
+        .collect();
+    (!meshes.is_empty()).then(|| meshes.join(", "))
+}
+
+/// compares an actor between maps returning nothing when it's the same
+fn entry(
+    before: &Asset,
+    after: &Asset,
+    name: &str,
+    actor: &actor::Actor,
+    other: &actor::Actor,
+    registry: Option<&Registry>,
+) -> Option<Entry> {
+    let (a, b) = (actor.transform(before), other.transform(after));
+    let transform = (!a.translation.abs_diff_eq(b.translation, 1e-4)
+        || !a.rotation.abs_diff_eq(b.rotation, 1e-4)
+        || !a.scale.abs_diff_eq(b.scale, 1e-4))
+    .then_some((a, b));
+    let (mut a, b) = (properties(before, actor), properties(after, other));
+    let mut changes = Vec::new();
+    for (path, val) in b {
+        match a.remove(&path) {
+            Some(old) if old == val => (),
+            old => changes.push(Change {
+                path,
+                before: old,
+                after: Some(val),
+            }),
+        }
+    }
+    changes.extend(a.into_iter().map(|(path, val)| Change {
+        path,
+        before: Some(val),
+        after: None,
+    }));
+    let meshes = (
+        class_meshes(before, actor, registry),
+        class_meshes(after, other, registry),
+    );
+    if meshes.0 != meshes.1 {
+        changes.push(Change {
+            path: "class meshes".into(),
+            before: meshes.0,
+            after: meshes.1,
+        })
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    if transform.is_none() && changes.is_empty() {
+        return None;
+    }
+    Some(Entry {
+        name: name.into(),
+        label: other.name.clone(),
+        class: other.class.clone(),
+        before: Some(actor.export),
+        after: Some(other.export),
+        transform,
+        changes,
+    })
+}
+
+/// matches actors by their full object name and compares them
+/// with the blueprint classes which have been loaded so their meshes are compared too
+pub fn diff(before: &Asset, after: &Asset, registry: Option<&Registry>) -> Vec<Entry> {
+    let (old, new) = (actors(before), actors(after));
+    let mut entries = Vec::new();
+    for (name, actor) in old.iter() {
+        match new.get(name) {
+            Some(other) => entries.extend(entry(before, after, name, actor, other, registry)),
+            None => entries.push(Entry {
+                name: name.clone(),
+                label: actor.name.clone(),
+                class: actor.class.clone(),
+                before: Some(actor.export),
+                after: None,
+                transform: None,
+                changes: Vec::new(),
+            }),
+        }
+    }
+    entries.extend(
+        new.into_iter()
+            .filter(|(name, _)| !old.contains_key(name))
+            .map(|(name, actor)| Entry {
+                name,
+                label: actor.name,
+                class: actor.class,
+                before: None,
+                after: Some(actor.export),
+                transform: None,
+                changes: Vec::new(),
+            }),
+    );
+    entries
+}
+
+#[test]
+fn changed() {
+    use unreal_asset::reader::archive_trait::ArchiveTrait;
+    let (mut before, mut after) = (regression::sample(), regression::sample());
+    let old = regression::stand_ins(&mut before).remove(0);
+    let new = regression::stand_ins(&mut after).remove(0);
+    assert!(entry(&before, &after, "", &old, &new, None).is_none());
+    let mut names = after.get_name_map();
+    let norm = after.asset_data.exports[new.export]
+        .get_normal_export_mut()
+        .unwrap();
+    props::set_float(&mut norm.properties, "Intensity", 8.0, &mut names);
+    props::set_vector(
+        &mut norm.properties,
+        actor::LOCATION,
+        bevy::math::DVec3::splat(500.0),
+        &mut names,
+    );
+    let changed = entry(&before, &after, "", &old, &new, None).unwrap();
+    assert!(changed.transform.is_some());
+    assert!(changed
+        .changes
+        .iter()
+        .any(|change| change.path == "Intensity"
+            && change.before.is_none()
+            && change.after.as_deref() == Some("8")));
+    // and the other way round it's been removed
+    let removed = entry(&after, &before, "", &new, &old, None).unwrap();
+    assert!(removed
+        .changes
+        .iter()
+        .any(|change| change.path == "Intensity" && change.after.is_none()));
+}
+
+/// prints the diff of two maps for stove diff <version> <before> <after>
+pub fn cli() -> AppExit {
+    let args: Vec<_> = std::env::args().skip(2).collect();
+    let [version, before, after] = args.as_slice() else {
+        eprintln!("usage: stove diff <version e.g 4.27> <before.umap> <after.umap>");
+        return AppExit::error();
+    };
+    let Some(version) = engine(version) else {
+        return AppExit::error();
+    };
+    let (before, after) = match (asset::open(before, version), asset::open(after, version)) {
+        (Ok(before), Ok(after)) => (before, after),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{e}");
+            return AppExit::error();
+        }
+    };
+    for entry in diff(&before, &after, None) {
+        print!("{entry}")
+    }
+    AppExit::Success
+}
+
+/// a box over an added or changed actor's meshes
+#[derive(Component)]
+pub struct Highlight;
+
+#[derive(Default, Resource)]
+pub struct Differ {
+    pub open: bool,
+    pub entries: Vec<Entry>,
+    // the map compared against so it can be refreshed
+    pub against: Option<std::path::PathBuf>,
+}
+
+pub fn compare(
+    _: Trigger<triggers::Diff>,
+    mut notif: EventWriter<Notif>,
+    appdata: Res<AppData>,
+    mut differ: ResMut<Differ>,
+    map: NonSend<Map>,
+    registry: Res<Registry>,
+) {
+    let Some((map, ..)) = &map.0 else {
+        notif.send(Notif {
+            message: "no map to compare".into(),
+            kind: Error,
+        });
+        return;
+    };
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("compare with")
+        .add_filter("maps", &["umap"])
+        .pick_file()
+    else {
+        return;
+    };
+    match asset::open(&path, appdata.version()) {
+        Ok(before) => {
+            differ.entries = diff(&before, map, Some(&registry));
+            differ.against = Some(path);
+            differ.open = true;
+        }
+        Err(e) => {
+            notif.send(Notif {
+                message: e.to_string(),
+                kind: Error,
+            });
+        }
+    }
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    mut differ: ResMut<Differ>,
+    appdata: Res<AppData>,
+    map: NonSend<Map>,
+    consts: Res<Constants>,
+    actors: Query<(Entity, &actor::Actor)>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+    added: Query<(), Added<actor::Actor>>,
+    mut removed: RemovedComponents<actor::Actor>,
+    registry: Res<Registry>,
+) {
+    // entries go by export index which moves when actors are added or removed
+    let moved = !added.is_empty() || removed.read().count() != 0;
+    // only touched when closing so the highlights aren't rebuilt every frame
+    let Some((map, ..)) = &map.0 else {
+        if differ.open {
+            differ.open = false
+        }
+        return;
+    };
+    if !differ.open {
+        return;
+    }
+    let entity = |export: usize| {
+        actors
+            .iter()
+            .find(|(_, actor)| actor.export == export)
+            .map(|(entity, _)| entity)
+    };
+    let mut open = true;
+    let mut select = None;
+    let mut refresh = moved;
+    egui::Window::new("diff")
+        .open(&mut open)
+        .default_width(400.0)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("⟳").on_hover_text("compare again").clicked() {
+                    refresh = true
+                }
+                if let Some(path) = &differ.against {
+                    ui.label(format!(
+                        "against {}",
+                        path.file_name().unwrap_or_default().to_string_lossy()
+                    ));
+                }
+            });
+            let count = |sign| {
+                differ
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.sign() == sign)
+                    .count()
+            };
+            ui.horizontal(|ui| {
+                ui.colored_label(egui::Color32::GREEN, format!("{} added", count("+")));
+                ui.colored_label(egui::Color32::RED, format!("{} removed", count("-")));
+                ui.colored_label(egui::Color32::YELLOW, format!("{} changed", count("~")));
+                if ui
+                    .button("select")
+                    .on_hover_text("select every added and changed actor")
+                    .clicked()
+                {
+                    select = Some(
+                        differ
+                            .entries
+                            .iter()
+                            .filter_map(|entry| entry.after.and_then(entity))
+                            .collect::<Vec<_>>(),
+                    )
+                }
+                if ui.button("copy").on_hover_text("copy as text").clicked() {
+                    ui.output_mut(|out| {
+                        out.copied_text = differ.entries.iter().map(ToString::to_string).collect()
+                    })
+                }
+            });
+            if differ.entries.is_empty() {
+                ui.label("no differences");
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for entry in differ.entries.iter() {
+                        let colour = match entry.sign() {
+                            "+" => egui::Color32::GREEN,
+                            "-" => egui::Color32::RED,
+                            _ => egui::Color32::YELLOW,
+                        };
+                        let header = egui::RichText::new(format!(
+                            "{} {} [{}]",
+                            entry.sign(),
+                            entry.label,
+                            entry.class
+                        ))
+                        .color(colour);
+                        egui::CollapsingHeader::new(header)
+                            .id_source(&entry.name)
+                            .show(ui, |ui| {
+                                if let Some(found) = entry.after.and_then(entity) {
+                                    if ui.button("select").clicked() {
+                                        select = Some(vec![found])
+                                    }
+                                }
+                                if let Some((before, after)) = &entry.transform {
+                                    ui.label(format!("transform: {}", describe(before)));
+                                    ui.label(format!("        -> {}", describe(after)));
+                                }
+                                egui::Grid::new(&entry.name).striped(true).show(ui, |ui| {
+                                    for change in entry.changes.iter() {
+                                        ui.label(&change.path);
+                                        ui.label(change.before.as_deref().unwrap_or("none"));
+                                        ui.label(change.after.as_deref().unwrap_or("none"));
+                                        ui.end_row();
+                                    }
+                                });
+                            });
+                    }
+                });
+        });
+    if refresh {
+        if let Some(path) = differ.against.clone() {
+            match asset::open(path, appdata.version()) {
+                Ok(before) => differ.entries = diff(&before, map, Some(&registry)),
+                Err(e) => {
+                    // the old entries would point at the wrong actors
+                    differ.entries.clear();
+                    notif.send(Notif {
+                        message: e.to_string(),
+                        kind: Error,
+                    });
+                }
+            }
+        }
+    }
+    if let Some(entities) = select {
+        for old in selected.iter() {
+            outliner::deselect(&mut commands, &mut cubes, &consts, old);
+        }
+        for &entity in entities.iter() {
+            outliner::select(&mut commands, &mut cubes, &consts, entity);
+        }
+        if entities.len() == 1 {
+            commands.trigger(triggers::Focus);
+        }
+    }
+    if !open {
+        differ.open = false
+    }
+}
+
+/// tints added and changed actors in the viewport while the diff is open
+pub fn highlight(
+    mut commands: Commands,
+    differ: Res<Differ>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut tints: ResMut<Assets<tint::Tint>>,
+    actors: Query<(Entity, &actor::Actor, Option<&Children>)>,
+    bounds: Query<&bevy::render::primitives::Aabb>,
+    highlights: Query<Entity, With<Highlight>>,
+    // meshes get bounds a frame after spawning so switching tabs waits for them
+    spawned: Query<(), (Added<bevy::render::primitives::Aabb>, Without<Highlight>)>,
+) {
+    if !differ.is_changed() && spawned.is_empty() {
+        return;
+    }
+    for entity in highlights.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    if !differ.open {
+        return;
+    }
+    for entry in differ.entries.iter() {
+        let colour = match entry.sign() {
+            "+" => LinearRgba::GREEN,
+            "~" => LinearRgba::rgb(1.0, 1.0, 0.0),
+            _ => continue,
+        };
+        let Some((entity, _, children)) = actors
+            .iter()
+            .find(|(_, actor, _)| Some(actor.export) == entry.after)
+        else {
+            continue;
+        };
+        // wire cubes are their own mesh and everything else has a child per section
+        for mesh in std::iter::once(entity).chain(children.into_iter().flatten().copied()) {
+            let Ok(aabb) = bounds.get(mesh) else {
+                continue;
+            };
+            commands.entity(mesh).with_children(|parent| {
+                parent.spawn((
+                    Highlight,
+                    MaterialMeshBundle {
+                        mesh: meshes.add(Cuboid::default()),
+                        material: tints.add(tint::Tint {
+                            colour: colour.with_alpha(0.25),
+                        }),
+                        transform: Transform::from_translation(aabb.center.into())
+                            .with_scale(Vec3::from(aabb.half_extents) * 2.0),
+                        ..default()
+                    },
+                    bevy::pbr::wireframe::NoWireframe,
+                ));
+            });
+        }
+    }
+}
@@ -0,0 +1,429 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use unreal_asset::{
+    containers::{NameMap, SharedResource},
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::{int_property::BytePropertyValue, Property, PropertyDataTrait},
+    reader::archive_trait::ArchiveTrait,
+    types::{
+        fname::{FName, ToSerializedName},
+        PackageIndex,
+    },
+};
+
+// a text version of a package for reviewing in version control
+// it only goes one way as it doesn't hold everything the package does
+// exports which aren't made of properties, map keys and properties the editor doesn't model
+// are left out or written as their type so it can't rebuild the package
+
+#[derive(Serialize, Deserialize)]
+pub struct Document {
+    pub names: Vec<String>,
+    pub imports: Vec<Import>,
+    pub exports: Vec<Export>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Import {
+    pub object_name: String,
+    pub class_package: String,
+    pub class_name: String,
+    pub outer: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Export {
+    pub object_name: String,
+    pub class: i32,
+    pub super_struct: i32,
+    pub template: i32,
+    pub outer: i32,
+    pub flags: u32,
+    pub serialization_before_serialization: Vec<i32>,
+    pub create_before_serialization: Vec<i32>,
+    pub serialization_before_create: Vec<i32>,
+    pub create_before_create: Vec<i32>,
+    // none for exports which aren't made of properties
+    pub properties: Option<Vec<Prop>>,
+    pub extras: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Prop {
+    pub name: String,
+    pub value: Value,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Floats(Vec<f64>),
+    Ints(Vec<i64>),
+    Name(String),
+    Enum(Option<String>),
+    Str(Option<String>),
+    Object(i32),
+    // an object by its path for patches which can't rely on indices
+    Reference(String),
+    Struct(Vec<Prop>),
+    Array(Vec<Value>),
+    // the property type which isn't modelled
+    Opaque(String),
+}
+
+fn indices(indices: &[PackageIndex]) -> Vec<i32> {
+    indices.iter().map(|index| index.index).collect()
+}
+
+pub fn value(property: &Property) -> Value {
+    match property {
+        Property::BoolProperty(p) => Value::Bool(p.value),
+        Property::Int8Property(p) => Value::Int(p.value as i64),
+        Property::Int16Property(p) => Value::Int(p.value as i64),
+        Property::IntProperty(p) => Value::Int(p.value as i64),
+        Property::Int64Property(p) => Value::Int(p.value),
+        Property::UInt16Property(p) => Value::UInt(p.value as u64),
+        Property::UInt32Property(p) => Value::UInt(p.value as u64),
+        Property::UInt64Property(p) => Value::UInt(p.value),
+        Property::FloatProperty(p) => Value::Float(p.value.0 as f64),
+        Property::DoubleProperty(p) => Value::Float(p.value.0),
+        Property::ByteProperty(p) => match &p.value {
+            BytePropertyValue::Byte(byte) => Value::UInt(*byte as u64),
+            BytePropertyValue::FName(name) => Value::Name(name.get_owned_content()),
+        },
+        Property::NameProperty(p) => Value::Name(p.value.get_owned_content()),
+        Property::EnumProperty(p) => Value::Enum(p.value.as_ref().map(FName::get_owned_content)),
+        Property::StrProperty(p) => Value::Str(p.value.clone()),
+        Property::ObjectProperty(p) => Value::Object(p.value.index),
+        Property::VectorProperty(p) => Value::Floats(vec![p.value.x.0, p.value.y.0, p.value.z.0]),
+        Property::RotatorProperty(p) => Value::Floats(vec![p.value.x.0, p.value.y.0, p.value.z.0]),
+        Property::Vector2DProperty(p) => Value::Floats(vec![p.value.x.0, p.value.y.0]),
+        Property::Vector4Property(p) => {
+            Value::Floats(vec![p.value.x.0, p.value.y.0, p.value.z.0, p.value.w.0])
+        }
+        Property::QuatProperty(p) => {
+            Value::Floats(vec![p.value.x.0, p.value.y.0, p.value.z.0, p.value.w.0])
+        }
+        Property::LinearColorProperty(p) => Value::Floats(vec![
+            p.color.r.0 as f64,
+            p.color.g.0 as f64,
+            p.color.b.0 as f64,
+            p.color.a.0 as f64,
+        ]),
+        Property::ColorProperty(p) => Value::Ints(vec![
+            p.color.r as i64,
+            p.color.g as i64,
+            p.color.b as i64,
+            p.color.a as i64,
+        ]),
+        Property::IntPointProperty(p) => Value::Ints(vec![p.value.x as i64, p.value.y as i64]),
+        Property::StructProperty(p) => Value::Struct(p.value.iter().map(prop).collect()),
+        Property::ArrayProperty(p) => Value::Array(p.value.iter().map(value).collect()),
+        Property::SetProperty(p) => Value::Array(p.value.value.iter().map(value).collect()),
+        // keys are left alone since they can't be added to anyway
+        Property::MapProperty(p) => Value::Array(p.value.iter().map(|(_, v)| value(v)).collect()),
+        property => Value::Opaque(property.to_serialized_name()),
+    }
+}
+
+fn prop(prop: &Property) -> Prop {
+    Prop {
+        name: prop.get_name().get_owned_content(),
+        value: value(prop),
+    }
+}
+
+/// writes a package out as a document
+pub fn document(map: &Asset) -> Document {
+    Document {
+        names: map
+            .get_name_map()
+            .get_ref()
+            .get_name_map_index_list()
+            .to_vec(),
+        imports: map
+            .imports
+            .iter()
+            .map(|import| Import {
+                object_name: import.object_name.get_owned_content(),
+                class_package: import.class_package.get_owned_content(),
+                class_name: import.class_name.get_owned_content(),
+                outer: import.outer_index.index,
+            })
+            .collect(),
+        exports: map
+            .asset_data
+            .exports
+            .iter()
+            .map(|ex| {
+                let base = ex.get_base_export();
+                let norm = ex.get_normal_export();
+                Export {
+                    object_name: base.object_name.get_owned_content(),
+                    class: base.class_index.index,
+                    super_struct: base.super_index.index,
+                    template: base.template_index.index,
+                    outer: base.outer_index.index,
+                    flags: base.object_flags.bits(),
+                    serialization_before_serialization: indices(
+                        &base.serialization_before_serialization_dependencies,
+                    ),
+                    create_before_serialization: indices(
+                        &base.create_before_serialization_dependencies,
+                    ),
+                    serialization_before_create: indices(
+                        &base.serialization_before_create_dependencies,
+                    ),
+                    create_before_create: indices(&base.create_before_create_dependencies),
+                    properties: norm.map(|norm| norm.properties.iter().map(prop).collect()),
+                    extras: norm.map(|norm| hex::encode(&norm.extras)),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// only replaces a name if it's actually different so untouched names stay as they were
+fn rename(names: &SharedResource<NameMap>, name: &mut FName, content: &str) {
+    if name.get_owned_content() != content {
+        *name = names.get_mut().add_fname(content)
+    }
+}
+
+fn floats<const N: usize>(path: &str, values: &[f64]) -> Result<[f64; N], String> {
+    values
+        .try_into()
+        .map_err(|_| format!("{path} needs {N} values"))
+}
+
+fn ints<const N: usize>(path: &str, values: &[i64]) -> Result<[i64; N], String> {
+    values
+        .try_into()
+        .map_err(|_| format!("{path} needs {N} values"))
+}
+
+/// sets a property to a value of the same shape
+pub fn apply(
+    names: &SharedResource<NameMap>,
+    prop: &mut Property,
+    value: &Value,
+    path: &str,
+) -> Result<(), String> {
+    match (prop, value) {
+        (_, Value::Opaque(_)) => (),
+        (Property::BoolProperty(p), Value::Bool(v)) => p.value = *v,
+        (Property::Int8Property(p), Value::Int(v)) => p.value = *v as _,
+        (Property::Int16Property(p), Value::Int(v)) => p.value = *v as _,
+        (Property::IntProperty(p), Value::Int(v)) => p.value = *v as _,
+        (Property::Int64Property(p), Value::Int(v)) => p.value = *v,
+        (Property::UInt16Property(p), Value::UInt(v)) => p.value = *v as _,
+        (Property::UInt32Property(p), Value::UInt(v)) => p.value = *v as _,
+        (Property::UInt64Property(p), Value::UInt(v)) => p.value = *v,
+        (Property::FloatProperty(p), Value::Float(v)) => p.value.0 = *v as _,
+        (Property::DoubleProperty(p), Value::Float(v)) => p.value.0 = *v,
+        (Property::ByteProperty(p), v) => match (&mut p.value, v) {
+            (BytePropertyValue::Byte(byte), Value::UInt(v)) => *byte = *v as _,
+            (BytePropertyValue::FName(name), Value::Name(v)) => rename(names, name, v),
+            _ => return Err(format!("{path} has the wrong type of value")),
+        },
+        (Property::NameProperty(p), Value::Name(v)) => rename(names, &mut p.value, v),
+        (Property::EnumProperty(p), Value::Enum(v)) => match (&mut p.value, v) {
+            (Some(name), Some(v)) => rename(names, name, v),
+            (value, v) => *value = v.as_deref().map(|v| names.get_mut().add_fname(v)),
+        },
+        (Property::StrProperty(p), Value::Str(v)) => p.value.clone_from(v),
+        (Property::ObjectProperty(p), Value::Object(v)) => p.value = PackageIndex::new(*v),
+        (Property::VectorProperty(p), Value::Floats(v)) => {
+            [p.value.x.0, p.value.y.0, p.value.z.0] = floats(path, v)?
+        }
+        (Property::RotatorProperty(p), Value::Floats(v)) => {
+            [p.value.x.0, p.value.y.0, p.value.z.0] = floats(path, v)?
+        }
+        (Property::Vector2DProperty(p), Value::Floats(v)) => {
+            [p.value.x.0, p.value.y.0] = floats(path, v)?
+        }
+        (Property::Vector4Property(p), Value::Floats(v)) => {
+            [p.value.x.0, p.value.y.0, p.value.z.0, p.value.w.0] = floats(path, v)?
+        }
+        (Property::QuatProperty(p), Value::Floats(v)) => {
+            [p.value.x.0, p.value.y.0, p.value.z.0, p.value.w.0] = floats(path, v)?
+        }
+        (Property::LinearColorProperty(p), Value::Floats(v)) => {
+            let [r, g, b, a] = floats(path, v)?;
+            (p.color.r.0, p.color.g.0, p.color.b.0, p.color.a.0) = (r as _, g as _, b as _, a as _)
+        }
+        (Property::ColorProperty(p), Value::Ints(v)) => {
+            let [r, g, b, a] = ints(path, v)?;
+            (p.color.r, p.color.g, p.color.b, p.color.a) = (r as _, g as _, b as _, a as _)
+        }
+        (Property::IntPointProperty(p), Value::Ints(v)) => {
+            let [x, y] = ints(path, v)?;
+            (p.value.x, p.value.y) = (x as _, y as _)
+        }
+        (Property::StructProperty(p), Value::Struct(v)) => {
+            props(names, &mut p.value, v, path)?;
+        }
+        (Property::ArrayProperty(p), Value::Array(v)) => {
+            array(names, &mut p.value, v, path)?;
+        }
+        (Property::SetProperty(p), Value::Array(v)) => {
+            array(names, &mut p.value.value, v, path)?;
+        }
+        (Property::MapProperty(p), Value::Array(v)) => {
+            if p.value.iter().count() != v.len() {
+                return Err(format!("{path} can't change how many entries it has"));
+            }
+            for (i, (entry, v)) in p.value.values_mut().zip(v).enumerate() {
+                apply(names, entry, v, &format!("{path}[{i}]"))?
+            }
+        }
+        _ => return Err(format!("{path} has the wrong type of value")),
+    }
+    Ok(())
+}
+
+/// resizes an array by copying its last entry and sets its entries
+fn array(
+    names: &SharedResource<NameMap>,
+    entries: &mut Vec<Property>,
+    values: &[Value],
+    path: &str,
+) -> Result<(), String> {
+    match entries.last().cloned() {
+        Some(last) => entries.resize(values.len(), last),
+        None if !values.is_empty() => {
+            return Err(format!("{path} is empty so there's nothing to add from"))
+        }
+        None => (),
+    }
+    for (i, (entry, v)) in entries.iter_mut().zip(values).enumerate() {
+        apply(names, entry, v, &format!("{path}[{i}]"))?
+    }
+    Ok(())
+}
+
+fn props(
+    names: &SharedResource<NameMap>,
+    props: &mut [Property],
+    values: &[Prop],
+    path: &str,
+) -> Result<(), String> {
+    if props.len() != values.len() {
+        return Err(format!("{path} can't change how many properties it has"));
+    }
+    for (prop, v) in props.iter_mut().zip(values) {
+        let path = format!("{path}.{}", v.name);
+        if prop.get_name().get_owned_content() != v.name {
+            return Err(format!(
+                "{path} doesn't match {}",
+                prop.get_name().get_owned_content()
+            ));
+        }
+        apply(names, prop, &v.value, &path)?
+    }
+    Ok(())
+}
+
+#[test]
+fn exported() {
+    let mut map = regression::sample();
+    let actor = regression::stand_ins(&mut map).remove(0);
+    let mut names = map.get_name_map();
+    let norm = map.asset_data.exports[actor.export]
+        .get_normal_export_mut()
+        .unwrap();
+    props::set_float(&mut norm.properties, "Intensity", 8.0, &mut names);
+    let text =
+        ron::ser::to_string_pretty(&document(&map), ron::ser::PrettyConfig::default()).unwrap();
+    let doc: Document = ron::from_str(&text).unwrap();
+    assert_eq!(doc.imports.len(), map.imports.len());
+    assert_eq!(doc.exports.len(), map.asset_data.exports.len());
+    let base = map.asset_data.exports[actor.export].get_base_export();
+    let ex = &doc.exports[actor.export];
+    assert_eq!(ex.object_name, base.object_name.get_owned_content());
+    assert_eq!(ex.outer, base.outer_index.index);
+    assert!(ex
+        .properties
+        .iter()
+        .flatten()
+        .any(|prop| prop.name == "Intensity" && prop.value == Value::Float(8.0)));
+    // the same package always reads the same
+    assert_eq!(
+        ron::ser::to_string_pretty(&document(&map), ron::ser::PrettyConfig::default()).unwrap(),
+        text
+    );
+}
+
+/// writes a document for stove export <version> <map> [out]
+pub fn export_cli() -> AppExit {
+    let args: Vec<_> = std::env::args().skip(2).collect();
+    let (version, path, out) = match args.as_slice() {
+        [version, path] => (version, path, None),
+        [version, path, out] => (version, path, Some(out)),
+        _ => {
+            eprintln!("usage: stove export <version e.g 4.27> <map.umap> [out.ron]");
+            return AppExit::error();
+        }
+    };
+    let Some(version) = engine(version) else {
+        return AppExit::error();
+    };
+    let map = match asset::open(path, version) {
+        Ok(map) => map,
+        Err(e) => {
+            eprintln!("{e}");
+            return AppExit::error();
+        }
+    };
+    let text = match ron::ser::to_string_pretty(&document(&map), ron::ser::PrettyConfig::default())
+    {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{e}");
+            return AppExit::error();
+        }
+    };
+    match out {
+        Some(out) => {
+            if let Err(e) = std::fs::write(out, text) {
+                eprintln!("{e}");
+                return AppExit::error();
+            }
+        }
+        None => println!("{text}"),
+    }
+    AppExit::Success
+}
+
+pub fn export(_: Trigger<triggers::ExportText>, mut notif: EventWriter<Notif>, map: NonSend<Map>) {
+    let Some((map, ..)) = &map.0 else {
+        notif.send(Notif {
+            message: "no map to export".into(),
+            kind: Error,
+        });
+        return;
+    };
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("export as text")
+        .add_filter("ron", &["ron"])
+        .save_file()
+    else {
+        return;
+    };
+    match ron::ser::to_string_pretty(&document(map), ron::ser::PrettyConfig::default())
+        .map_err(|e| e.to_string())
+        .and_then(|text| std::fs::write(path, text).map_err(|e| e.to_string()))
+    {
+        Ok(_) => notif.send(Notif {
+            message: "map exported".into(),
+            kind: Success,
+        }),
+        Err(e) => notif.send(Notif {
+            message: e,
+            kind: Error,
+        }),
+    };
+}
@@ -56,11 +56,20 @@ pub fn shortcuts(
     if keys.just_released(KeyCode::KeyF) {
         commands.trigger(triggers::Focus);
     }
+    if keys.just_released(KeyCode::KeyD) && ctrl {
+        commands.trigger(triggers::Duplicate(true));
+    }
     if keys.just_released(KeyCode::KeyC) && ctrl {
-        commands.trigger(triggers::Copy);
+        match shift {
+            true => commands.trigger(triggers::CopyLocation),
+            false => commands.trigger(triggers::Copy),
+        }
     }
     if keys.just_released(KeyCode::KeyV) && ctrl {
-        commands.trigger(triggers::Paste);
+        match shift {
+            true => commands.trigger(triggers::PasteLocation),
+            false => commands.trigger(triggers::Paste),
+        }
     }
     if keys.just_released(KeyCode::Escape) {
         commands.trigger(triggers::Deselect);
@@ -71,6 +80,12 @@ pub fn shortcuts(
     if keys.just_released(KeyCode::KeyH) {
         commands.trigger(triggers::Hide)
     }
+    if keys.just_released(KeyCode::KeyR) {
+        commands.trigger(triggers::References)
+    }
+    if keys.just_released(KeyCode::KeyI) {
+        commands.trigger(triggers::Inspector)
+    }
 }
 
 // an edited version of the original default input map
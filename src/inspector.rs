@@ -0,0 +1,330 @@
+use super::*;
+use unreal_asset::{
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    reader::archive_trait::ArchiveTrait,
+    types::{PackageIndex, PackageIndexTrait},
+};
+
+#[derive(Default, PartialEq, Clone, Copy)]
+enum Tab {
+    #[default]
+    Exports,
+    Imports,
+    Names,
+}
+
+#[derive(Default, Resource)]
+pub struct Inspector {
+    pub open: bool,
+    tab: Tab,
+    filter: String,
+    picked: Option<PackageIndex>,
+    // row to scroll to after following a link
+    scroll: Option<usize>,
+}
+
+impl Inspector {
+    /// shows the details of an object and scrolls to it
+    pub fn pick(&mut self, index: PackageIndex) {
+        match index.index {
+            0 => return,
+            i if i > 0 => {
+                self.tab = Tab::Exports;
+                self.scroll = Some(i as usize - 1)
+            }
+            i => {
+                self.tab = Tab::Imports;
+                self.scroll = Some(-i as usize - 1)
+            }
+        }
+        // the row wouldn't be found if it's filtered out
+        self.filter.clear();
+        self.picked = Some(index);
+        self.open = true;
+    }
+}
+
+pub fn toggle(
+    _: Trigger<triggers::Inspector>,
+    mut inspector: ResMut<Inspector>,
+    selected: Query<&actor::Actor, With<actor::Selected>>,
+) {
+    match selected.get_single() {
+        Ok(actor) if !inspector.open => inspector.pick(PackageIndex::new(actor.export as i32 + 1)),
+        _ => inspector.open = !inspector.open,
+    }
+}
+
+/// a link to an object which is just a label when null
+fn link(ui: &mut egui::Ui, map: &Asset, index: PackageIndex, follow: &mut Option<PackageIndex>) {
+    match index.index {
+        0 => {
+            ui.weak("null");
+        }
+        _ => {
+            if ui.link(refs::name(map, index)).clicked() {
+                *follow = Some(index)
+            }
+        }
+    }
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut inspector: ResMut<Inspector>,
+    mut graph: ResMut<refs::Graph>,
+    mut map: NonSendMut<Map>,
+    consts: Res<Constants>,
+    actors: Query<(Entity, &actor::Actor)>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+) {
+    let Some((map, _, export_names, import_names)) = &mut map.0 else {
+        inspector.open = false;
+        return;
+    };
+    if !inspector.open {
+        return;
+    }
+    let actors: Vec<_> = actors.iter().collect();
+    let mut open = true;
+    let mut follow = None;
+    let mut select = None;
+    let mut references = None;
+    let inspector = inspector.as_mut();
+    egui::Window::new("package")
+        .open(&mut open)
+        .default_width(400.0)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                for (tab, label) in [
+                    (Tab::Exports, "exports"),
+                    (Tab::Imports, "imports"),
+                    (Tab::Names, "names"),
+                ] {
+                    ui.selectable_value(&mut inspector.tab, tab, label);
+                }
+            });
+            ui.add(egui::TextEdit::singleline(&mut inspector.filter).hint_text("filter"));
+            let filter = inspector.filter.to_ascii_lowercase();
+            let keep =
+                |text: &str| filter.is_empty() || text.to_ascii_lowercase().contains(&filter);
+            // labels and what they link to
+            let rows: Vec<(String, Option<PackageIndex>)> = match inspector.tab {
+                Tab::Exports => (0..map.asset_data.exports.len())
+                    .map(|i| {
+                        let index = PackageIndex::new(i as i32 + 1);
+                        let class = map.asset_data.exports[i].get_base_export().class_index;
+                        let class = match map.get_import(class) {
+                            Some(import) => import.object_name.get_owned_content(),
+                            None => refs::name(map, class),
+                        };
+                        (format!("{} [{class}]", refs::name(map, index)), Some(index))
+                    })
+                    .filter(|(label, _)| keep(label.as_str()))
+                    .collect(),
+                Tab::Imports => map
+                    .imports
+                    .iter()
+                    .enumerate()
+                    .map(|(i, import)| {
+                        let index = PackageIndex::new(-(i as i32) - 1);
+                        (
+                            format!(
+                                "{} [{}]",
+                                refs::name(map, index),
+                                import.class_name.get_owned_content()
+                            ),
+                            Some(index),
+                        )
+                    })
+                    .filter(|(label, _)| keep(label.as_str()))
+                    .collect(),
+                Tab::Names => {
+                    let names = map.get_name_map();
+                    let names = names.get_ref();
+                    let rows = names
+                        .get_name_map_index_list()
+                        .iter()
+                        .enumerate()
+                        .map(|(i, name)| (format!("{i}: {name}"), None))
+                        .filter(|(label, _)| keep(label.as_str()))
+                        .collect();
+                    rows
+                }
+            };
+            let height = ui.text_style_height(&egui::TextStyle::Body);
+            let spacing = ui.spacing().item_spacing.y;
+            let mut area = egui::ScrollArea::vertical()
+                .id_source("package")
+                .max_height(match inspector.tab {
+                    Tab::Names => ui.available_height(),
+                    _ => ui.available_height() * 0.5,
+                })
+                .auto_shrink([false, true]);
+            if let Some(row) = inspector.scroll.take() {
+                area = area.vertical_scroll_offset(row as f32 * (height + spacing))
+            }
+            area.show_rows(ui, height, rows.len(), |ui, range| {
+                ui.with_layout(egui::Layout::default().with_cross_justify(true), |ui| {
+                    for (label, index) in &rows[range] {
+                        match index {
+                            Some(index) => {
+                                if ui
+                                    .selectable_label(inspector.picked == Some(*index), label)
+                                    .clicked()
+                                {
+                                    inspector.picked = Some(*index)
+                                }
+                            }
+                            None => {
+                                ui.label(label);
+                            }
+                        }
+                    }
+                })
+            });
+            if inspector.tab == Tab::Names {
+                ui.weak("the name map is rebuilt from the names in use on save");
+                return;
+            }
+            let Some(picked) = inspector
+                .picked
+                .filter(|picked| picked.is_import() == (inspector.tab == Tab::Imports))
+            else {
+                return;
+            };
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.heading(refs::name(map, picked));
+                if let Some(entity) = refs::owner(map, picked, &actors) {
+                    if ui
+                        .button("select")
+                        .on_hover_text("select its actor")
+                        .clicked()
+                    {
+                        select = Some(entity)
+                    }
+                }
+                if ui.button("references").clicked() {
+                    references = Some(picked)
+                }
+            });
+            egui::ScrollArea::vertical()
+                .id_source("details")
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    if picked.is_import() {
+                        let i = -picked.index as usize - 1;
+                        let Some(import) = map.imports.get_mut(i) else {
+                            return;
+                        };
+                        let old = import.object_name.get_owned_content();
+                        egui::Grid::new("import").num_columns(2).show(ui, |ui| {
+                            ui.label("object name");
+                            actor::fname(ui, &mut import.object_name);
+                            ui.end_row();
+                            ui.label("class package");
+                            actor::fname(ui, &mut import.class_package);
+                            ui.end_row();
+                            ui.label("class name");
+                            actor::fname(ui, &mut import.class_name);
+                            ui.end_row();
+                        });
+                        let new = import.object_name.get_owned_content();
+                        if new != old && import_names.get(i) == Some(&old) {
+                            import_names[i] = new
+                        }
+                        let outer = import.outer_index;
+                        ui.horizontal(|ui| {
+                            ui.label("outer");
+                            link(ui, map, outer, &mut follow)
+                        });
+                        return;
+                    }
+                    let i = picked.index as usize - 1;
+                    let Some(ex) = map.asset_data.exports.get_mut(i) else {
+                        return;
+                    };
+                    let base = ex.get_base_export_mut();
+                    let old = base.object_name.get_owned_content();
+                    ui.horizontal(|ui| {
+                        ui.label("object name");
+                        actor::fname(ui, &mut base.object_name);
+                    });
+                    let new = base.object_name.get_owned_content();
+                    // actors are listed by their label so only follow the object name otherwise
+                    if new != old && export_names.get(i) == Some(&old) {
+                        export_names[i] = new
+                    }
+                    let base = map.asset_data.exports[i].get_base_export();
+                    egui::Grid::new("export").num_columns(2).show(ui, |ui| {
+                        for (label, index) in [
+                            ("class", base.class_index),
+                            ("outer", base.outer_index),
+                            ("super", base.super_index),
+                            ("template", base.template_index),
+                        ] {
+                            ui.label(label);
+                            link(ui, map, index, &mut follow);
+                            ui.end_row();
+                        }
+                        ui.label("serial size");
+                        ui.label(base.serial_size.to_string());
+                        ui.end_row();
+                        ui.label("serial offset");
+                        ui.label(base.serial_offset.to_string());
+                        ui.end_row();
+                        ui.label("object flags");
+                        ui.label(format!("{:#010x}", base.object_flags.bits()));
+                        ui.end_row();
+                        if let Some(norm) = map.asset_data.exports[i].get_normal_export() {
+                            ui.label("properties");
+                            ui.label(norm.properties.len().to_string());
+                            ui.end_row();
+                        }
+                    });
+                    for (heading, deps) in [
+                        (
+                            "serialization before serialization",
+                            &base.serialization_before_serialization_dependencies,
+                        ),
+                        (
+                            "create before serialization",
+                            &base.create_before_serialization_dependencies,
+                        ),
+                        (
+                            "serialization before create",
+                            &base.serialization_before_create_dependencies,
+                        ),
+                        (
+                            "create before create",
+                            &base.create_before_create_dependencies,
+                        ),
+                    ] {
+                        egui::CollapsingHeader::new(format!("{heading} ({})", deps.len()))
+                            .id_source(heading)
+                            .show(ui, |ui| {
+                                for dep in deps {
+                                    link(ui, map, *dep, &mut follow)
+                                }
+                            });
+                    }
+                });
+        });
+    if let Some(index) = follow {
+        inspector.pick(index)
+    }
+    if let Some(index) = references {
+        graph.focus(index)
+    }
+    if let Some(entity) = select {
+        for old in selected.iter() {
+            outliner::deselect(&mut commands, &mut cubes, &consts, old);
+        }
+        outliner::select(&mut commands, &mut cubes, &consts, entity);
+        commands.trigger(triggers::Focus);
+    }
+    inspector.open &= open;
+}
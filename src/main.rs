@@ -8,16 +8,40 @@ use egui_notify::ToastLevel::{Error, Info, Success, Warning};
 
 mod action;
 mod actor;
+mod array;
 mod asset;
+mod attachment;
+mod blueprint;
+mod clipboard;
+mod deletion;
 mod dialog;
+mod diff;
+mod document;
 mod extras;
 mod input;
+mod inspector;
+mod outliner;
+mod patch;
 mod persistence;
 mod picking;
+mod prefab;
+mod preview;
+mod props;
+mod query;
+mod refs;
+#[cfg(test)]
+mod regression;
+mod replace;
+mod scatter;
+mod spline;
 mod startup;
+mod tabs;
+mod tint;
 mod triggers;
 mod ui;
 mod unlit;
+mod validate;
+mod volume;
 mod wire;
 
 type Asset = unreal_asset::Asset<Wrapper>;
@@ -26,8 +50,17 @@ type Export = unreal_asset::Export<unreal_asset::types::PackageIndex>;
 #[derive(Default)]
 struct Map(Option<(Asset, Option<std::path::PathBuf>, Vec<String>, Vec<String>)>);
 
+// the donor with its path, actors, which of them are picked and the version it was read as
 #[derive(Default)]
-struct Transplant(Option<(Asset, Vec<actor::Actor>, Vec<usize>)>);
+struct Transplant(
+    Option<(
+        Asset,
+        std::path::PathBuf,
+        Vec<actor::Actor>,
+        Vec<usize>,
+        usize,
+    )>,
+);
 
 #[derive(Event)]
 struct Notif {
@@ -40,8 +73,11 @@ struct Notifs(egui_notify::Toasts);
 
 #[derive(Default, Resource)]
 struct Registry {
-    meshes: std::collections::BTreeMap<String, (Handle<Mesh>, Option<String>)>,
-    mats: std::collections::BTreeMap<String, Handle<unlit::Unlit>>,
+    // material sections with their slot and the mesh's own materials per slot
+    meshes: std::collections::BTreeMap<String, (Vec<(Handle<Mesh>, usize)>, Vec<String>)>,
+    // none when the material has no texture which can be read
+    mats: std::collections::BTreeMap<String, Option<Handle<unlit::Unlit>>>,
+    classes: std::collections::BTreeMap<String, Vec<blueprint::Template>>,
 }
 
 #[derive(Default, Resource)]
@@ -57,8 +93,23 @@ struct AppData {
     wireframe: bool,
     script: String,
     query: String,
+    query_error: Option<String>,
+    outliner: outliner::Outliner,
     cap: bool,
     rate: f64,
+    placement: Placement,
+}
+
+/// where duplicated and transplanted actors go
+#[derive(Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Placement {
+    // the same place as the actors they came from
+    #[default]
+    Original,
+    // the surface under the cursor
+    Cursor,
+    // a little in front of the camera
+    Camera,
 }
 
 #[derive(Clone)]
@@ -97,6 +148,7 @@ enum Drag {
     Translate(Vec3),
     Scale(Vec2),
     Rotate(Vec2, Vec2),
+    Point(Entity, usize, Vec3),
 }
 
 #[allow(clippy::upper_case_acronyms)]
@@ -151,6 +203,21 @@ fn config() -> Option<std::path::PathBuf> {
     dirs::config_dir().map(|path| path.join("stove"))
 }
 
+/// gets an engine version from its name for the command line
+fn engine(name: &str) -> Option<unreal_asset::engine_version::EngineVersion> {
+    let version = VERSIONS
+        .iter()
+        .find(|(_, version)| *version == name)
+        .map(|(version, _)| *version);
+    if version.is_none() {
+        eprintln!(
+            "unknown version {name} - try one of {}",
+            VERSIONS.map(|(_, name)| name).join(", ")
+        )
+    }
+    version
+}
+
 fn activity() -> discord_rich_presence::activity::Activity<'static> {
     use discord_rich_presence::activity::*;
     Activity::new()
@@ -163,6 +230,12 @@ fn activity() -> discord_rich_presence::activity::Activity<'static> {
 }
 
 fn main() -> AppExit {
+    match std::env::args().nth(1).as_deref() {
+        Some("diff") => return diff::cli(),
+        Some("export") => return document::export_cli(),
+        Some("patch") => return patch::cli(),
+        _ => (),
+    }
     App::new()
         .add_plugins((
             DefaultPlugins
@@ -183,6 +256,7 @@ fn main() -> AppExit {
                     ..default()
                 }),
             unlit::UnlitPlugin,
+            tint::TintPlugin,
             wire::WirePlugin,
             bevy_egui::EguiPlugin,
             smooth_bevy_cameras::LookTransformPlugin,
@@ -197,6 +271,7 @@ fn main() -> AppExit {
         ))
         .init_non_send_resource::<Map>()
         .init_non_send_resource::<Transplant>()
+        .init_non_send_resource::<tabs::Tabs>()
         .init_resource::<Notifs>()
         .init_resource::<Registry>()
         .init_resource::<Focus>()
@@ -207,6 +282,16 @@ fn main() -> AppExit {
         .init_resource::<FromContent>()
         .init_resource::<Client>()
         .init_resource::<Content>()
+        .init_resource::<refs::Graph>()
+        .init_resource::<inspector::Inspector>()
+        .init_resource::<validate::Validator>()
+        .init_resource::<diff::Differ>()
+        .init_resource::<patch::Recorder>()
+        .init_resource::<prefab::Library>()
+        .init_resource::<deletion::Deletion>()
+        .init_resource::<array::Array>()
+        .init_resource::<scatter::Scatter>()
+        .init_resource::<replace::Replace>()
         .insert_resource(bevy::pbr::wireframe::WireframeConfig {
             global: false,
             default_color: bevy::color::palettes::css::WHITE.into(),
@@ -220,6 +305,7 @@ fn main() -> AppExit {
                 startup::discord,
                 startup::camera,
                 startup::consts,
+                preview::setup,
                 (persistence::load, startup::check_args).chain(),
             ),
         )
@@ -234,32 +320,95 @@ fn main() -> AppExit {
                         }
                     }
                 },
+                tabs::bar,
                 ui::sidebar,
                 ui::notifs,
+                (
+                    refs::panel,
+                    inspector::panel,
+                    validate::panel,
+                    diff::panel,
+                    diff::highlight,
+                    patch::panel,
+                    prefab::panel,
+                    deletion::panel,
+                    array::panel,
+                    scatter::panel,
+                    replace::panel,
+                ),
+                (preview::sync, preview::panel).chain(),
                 input::shortcuts,
                 action::approach,
+                attachment::mark,
+                spline::spawn,
+                spline::handles,
+                volume::spawn,
             ),
         )
         // post update because egui isn't built until update
         .add_systems(
             PostUpdate,
-            ((picking::pick, picking::drag).chain(), input::camera),
+            (
+                (
+                    picking::pick,
+                    prefab::place,
+                    scatter::brush,
+                    picking::drag,
+                    attachment::follow,
+                    patch::transforms,
+                    tabs::track,
+                )
+                    .chain(),
+                input::camera,
+            ),
         )
         .observe(dialog::open)
         .observe(dialog::from_content)
         .observe(dialog::save_as)
         .observe(dialog::add_pak)
         .observe(dialog::transplant_from)
+        .observe(dialog::transplant)
+        .observe(dialog::donor_version)
         .observe(dialog::transplant_into)
+        .observe(dialog::apply_patch)
+        .observe(dialog::switch_tab)
+        .observe(tabs::send)
+        .observe(tabs::close)
+        .observe(tabs::edited::<triggers::Record>)
+        .observe(tabs::edited::<triggers::PasteLocation>)
+        .observe(tabs::edited::<triggers::RefreshSpline>)
+        .observe(tabs::edited::<triggers::RefreshVolume>)
         .observe(action::duplicate)
         .observe(action::delete)
         .observe(action::focus)
-        .observe(action::copy)
-        .observe(action::paste)
+        .observe(action::copy_location)
+        .observe(action::paste_location)
+        .observe(clipboard::copy)
+        .observe(clipboard::paste)
         .observe(action::deselect)
         .observe(action::fullscreen)
         .observe(action::hide)
         .observe(action::load_paks)
+        .observe(action::retexture)
+        .observe(refs::toggle)
+        .observe(inspector::toggle)
+        .observe(validate::run)
+        .observe(diff::compare)
+        .observe(document::export)
+        .observe(patch::record)
+        .observe(patch::toggle)
+        .observe(prefab::toggle)
+        .observe(prefab::spawn)
+        .observe(array::toggle)
+        .observe(array::spawn)
+        .observe(scatter::toggle)
+        .observe(scatter::spawn)
+        .observe(replace::toggle)
+        .observe(replace::replace)
+        .observe(spline::refresh)
+        .observe(spline::insert_point)
+        .observe(spline::delete_point)
+        .observe(volume::refresh)
         .run()
 }
 
@@ -0,0 +1,384 @@
+use super::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use unreal_asset::{exports::ExportBaseTrait, types::PackageIndexTrait};
+
+#[derive(Default, PartialEq, Clone, Copy)]
+pub enum Group {
+    #[default]
+    None,
+    Parent,
+    Class,
+}
+
+impl Group {
+    const ALL: [Self; 3] = [Self::None, Self::Parent, Self::Class];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Parent => "attachment",
+            Self::Class => "class",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Outliner {
+    group: Group,
+    include: String,
+    exclude: String,
+    collapsed: HashSet<egui::Id>,
+    // where range selections start from
+    anchor: Option<Entity>,
+    // rows shown last frame so keyboard navigation only scrolls when needed
+    visible: std::ops::Range<usize>,
+    // what the rows were built from so they're only rebuilt when it changes
+    built: Option<(Group, String, String, Vec<Entity>)>,
+    rows: Vec<Row>,
+}
+
+enum Row {
+    Group(String, usize),
+    // depth and whether it has children
+    Actor(Entity, usize, bool),
+}
+
+/// comma separated lowercase terms
+fn terms(filter: &str) -> Vec<String> {
+    filter
+        .split(',')
+        .map(|term| term.trim().to_ascii_lowercase())
+        .filter(|term| !term.is_empty())
+        .collect()
+}
+
+/// the actor export a component belongs to
+fn owner(map: &Asset, component: usize) -> Option<usize> {
+    let outer = map.asset_data.exports[component]
+        .get_base_export()
+        .outer_index;
+    (outer.is_export() && outer.index as usize <= map.asset_data.exports.len())
+        .then(|| outer.index as usize - 1)
+}
+
+impl Outliner {
+    fn build(&self, map: &Asset, mut actors: Vec<(Entity, &actor::Actor)>) -> Vec<Row> {
+        let (include, exclude) = (terms(&self.include), terms(&self.exclude));
+        actors.retain(|(_, actor)| {
+            let class = actor.class.to_ascii_lowercase();
+            (include.is_empty() || include.iter().any(|term| class.contains(term)))
+                && !exclude.iter().any(|term| class.contains(term))
+        });
+        actors.sort_by_key(|(_, actor)| actor.export);
+        let mut rows = Vec::with_capacity(actors.len());
+        match self.group {
+            Group::None => rows.extend(
+                actors
+                    .into_iter()
+                    .map(|(entity, _)| Row::Actor(entity, 0, false)),
+            ),
+            Group::Class => {
+                let mut groups = BTreeMap::<_, Vec<_>>::new();
+                for (entity, actor) in actors {
+                    groups.entry(actor.class.clone()).or_default().push(entity)
+                }
+                for (label, entities) in groups {
+                    let open = !self.collapsed.contains(&egui::Id::new(&label));
+                    rows.push(Row::Group(label, entities.len()));
+                    if open {
+                        rows.extend(
+                            entities
+                                .into_iter()
+                                .map(|entity| Row::Actor(entity, 1, false)),
+                        )
+                    }
+                }
+            }
+            Group::Parent => {
+                let exports: HashMap<_, _> = actors
+                    .iter()
+                    .map(|(entity, actor)| (actor.export, *entity))
+                    .collect();
+                let mut children = HashMap::<_, Vec<_>>::new();
+                let mut roots = Vec::new();
+                for (entity, actor) in actors.iter() {
+                    match actor
+                        .parent(map)
+                        .and_then(|parent| owner(map, parent))
+                        .filter(|parent| parent != &actor.export)
+                        .and_then(|parent| exports.get(&parent))
+                    {
+                        Some(parent) => children.entry(*parent).or_default().push(*entity),
+                        None => roots.push(*entity),
+                    }
+                }
+                let mut visited = HashSet::new();
+                for root in roots {
+                    self.walk(root, 0, &children, &mut visited, &mut rows)
+                }
+                // actors attached in a cycle are never reached from a root
+                for (entity, _) in actors {
+                    if !visited.contains(&entity) {
+                        self.walk(entity, 0, &children, &mut visited, &mut rows)
+                    }
+                }
+            }
+        }
+        rows
+    }
+
+    fn walk(
+        &self,
+        entity: Entity,
+        depth: usize,
+        children: &HashMap<Entity, Vec<Entity>>,
+        visited: &mut HashSet<Entity>,
+        rows: &mut Vec<Row>,
+    ) {
+        if !visited.insert(entity) {
+            return;
+        }
+        let kids = children.get(&entity).filter(|kids| !kids.is_empty());
+        rows.push(Row::Actor(entity, depth, kids.is_some()));
+        if self.collapsed.contains(&egui::Id::new(entity)) {
+            // still mark them so they aren't treated as roots
+            let mut stack: Vec<_> = kids.into_iter().flatten().copied().collect();
+            while let Some(kid) = stack.pop() {
+                if visited.insert(kid) {
+                    stack.extend(children.get(&kid).into_iter().flatten().copied())
+                }
+            }
+            return;
+        }
+        for &kid in kids.into_iter().flatten() {
+            self.walk(kid, depth + 1, children, visited, rows)
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        map: &Asset,
+        commands: &mut Commands,
+        consts: &Constants,
+        actors: Vec<(Entity, &actor::Actor)>,
+        selected: &[Entity],
+        cubes: &mut Query<&mut Handle<wire::Wire>>,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label("group by:");
+            egui::ComboBox::from_id_source("group")
+                .selected_text(self.group.label())
+                .show_ui(ui, |ui| {
+                    for group in Group::ALL {
+                        ui.selectable_value(&mut self.group, group, group.label());
+                    }
+                });
+        });
+        ui.collapsing("class filters", |ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.include)
+                    .hint_text("include e.g. StaticMeshActor, Light"),
+            );
+            ui.add(egui::TextEdit::singleline(&mut self.exclude).hint_text("exclude"));
+        });
+        let names: HashMap<_, _> = actors.iter().copied().collect();
+        let mut set: Vec<_> = actors.iter().map(|(entity, _)| *entity).collect();
+        set.sort();
+        let from = (self.group, self.include.clone(), self.exclude.clone(), set);
+        if self.built.as_ref() != Some(&from) {
+            self.rows = self.build(map, actors);
+            self.built = Some(from);
+        }
+        // taken so the rows can be shown while the outliner changes
+        let rows = std::mem::take(&mut self.rows);
+        let order: Vec<_> = rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Actor(entity, ..) => Some(*entity),
+                Row::Group(..) => None,
+            })
+            .collect();
+        let height = ui.text_style_height(&egui::TextStyle::Body);
+        let spacing = ui.spacing().item_spacing.y;
+        let mut area = egui::ScrollArea::both()
+            .id_source("actors")
+            .auto_shrink([false, true])
+            .max_height(ui.available_height() * 0.5);
+        // arrow keys move through the list while it's hovered
+        let (up, down, shift, ctrl) = ui.input(|input| {
+            (
+                input.key_pressed(egui::Key::ArrowUp),
+                input.key_pressed(egui::Key::ArrowDown),
+                input.modifiers.shift,
+                input.modifiers.ctrl,
+            )
+        });
+        if (up || down) && ui.ui_contains_pointer() && !order.is_empty() {
+            let next = match self
+                .anchor
+                .and_then(|anchor| order.iter().position(|entity| entity == &anchor))
+            {
+                Some(i) if up => i.saturating_sub(1),
+                Some(i) => (i + 1).min(order.len() - 1),
+                None => 0,
+            };
+            if !shift {
+                deselect_all(commands, cubes, consts, selected);
+            }
+            select(commands, cubes, consts, order[next]);
+            self.anchor = Some(order[next]);
+            if let Some(row) = rows
+                .iter()
+                .position(|row| matches!(row, Row::Actor(entity, ..) if entity == &order[next]))
+                .filter(|row| !self.visible.contains(row))
+            {
+                area = area.vertical_scroll_offset(row as f32 * (height + spacing));
+            }
+        }
+        area.show_rows(ui, height, rows.len(), |ui, range| {
+            self.visible = range.clone();
+            ui.with_layout(egui::Layout::default().with_cross_justify(true), |ui| {
+                let indent = ui.spacing().indent;
+                for row in &rows[range] {
+                    match row {
+                        Row::Group(label, count) => {
+                            let id = egui::Id::new(label);
+                            let open = !self.collapsed.contains(&id);
+                            if ui
+                                .selectable_label(
+                                    false,
+                                    egui::RichText::new(format!(
+                                        "{} {label} ({count})",
+                                        if open { "⏷" } else { "⏵" }
+                                    ))
+                                    .strong(),
+                                )
+                                .clicked()
+                            {
+                                self.toggle(id)
+                            }
+                        }
+                        &Row::Actor(entity, depth, parent) => {
+                            ui.horizontal(|ui| {
+                                ui.add_space(depth as f32 * indent);
+                                if parent {
+                                    let id = egui::Id::new(entity);
+                                    if ui
+                                        .small_button(match self.collapsed.contains(&id) {
+                                            true => "⏵",
+                                            false => "⏷",
+                                        })
+                                        .clicked()
+                                    {
+                                        self.toggle(id)
+                                    }
+                                }
+                                let highlighted = selected.contains(&entity);
+                                let Some(actor) = names.get(&entity) else {
+                                    return;
+                                };
+                                let response = ui
+                                    .selectable_label(highlighted, &actor.display)
+                                    .on_hover_text(&actor.class)
+                                    .interact(egui::Sense::drag());
+                                // dropping onto another tab copies the actors into it
+                                response.dnd_set_drag_payload(entity);
+                                if !response.clicked() {
+                                    return;
+                                }
+                                match (shift, ctrl) {
+                                    (true, _) => {
+                                        let from = self
+                                            .anchor
+                                            .and_then(|anchor| {
+                                                order.iter().position(|entity| entity == &anchor)
+                                            })
+                                            .unwrap_or_default();
+                                        let to = order
+                                            .iter()
+                                            .position(|other| other == &entity)
+                                            .unwrap_or_default();
+                                        if !ctrl {
+                                            deselect_all(commands, cubes, consts, selected);
+                                        }
+                                        for &entity in &order[from.min(to)..=from.max(to)] {
+                                            select(commands, cubes, consts, entity)
+                                        }
+                                    }
+                                    (false, true) => {
+                                        match highlighted {
+                                            true => deselect(commands, cubes, consts, entity),
+                                            false => select(commands, cubes, consts, entity),
+                                        }
+                                        self.anchor = Some(entity);
+                                    }
+                                    (false, false) => {
+                                        deselect_all(commands, cubes, consts, selected);
+                                        select(commands, cubes, consts, entity);
+                                        self.anchor = Some(entity);
+                                    }
+                                }
+                            });
+                        }
+                    }
+                }
+            })
+        });
+        self.rows = rows;
+    }
+
+    fn toggle(&mut self, id: egui::Id) {
+        if !self.collapsed.remove(&id) {
+            self.collapsed.insert(id);
+        }
+        self.built = None;
+    }
+}
+
+pub fn select(
+    commands: &mut Commands,
+    cubes: &mut Query<&mut Handle<wire::Wire>>,
+    consts: &Constants,
+    entity: Entity,
+) {
+    match cubes.get_mut(entity) {
+        Ok(mut mat) => {
+            commands.entity(entity).insert(actor::Selected);
+            *mat = consts.selected.clone_weak();
+        }
+        Err(_) => {
+            commands
+                .entity(entity)
+                .insert(actor::SelectedBundle::default());
+        }
+    }
+}
+
+pub fn deselect(
+    commands: &mut Commands,
+    cubes: &mut Query<&mut Handle<wire::Wire>>,
+    consts: &Constants,
+    entity: Entity,
+) {
+    match cubes.get_mut(entity) {
+        Ok(mut mat) => {
+            commands.entity(entity).remove::<actor::Selected>();
+            *mat = consts.unselected.clone_weak();
+        }
+        Err(_) => {
+            commands.entity(entity).remove::<actor::SelectedBundle>();
+        }
+    }
+}
+
+fn deselect_all(
+    commands: &mut Commands,
+    cubes: &mut Query<&mut Handle<wire::Wire>>,
+    consts: &Constants,
+    selected: &[Entity],
+) {
+    for &entity in selected {
+        deselect(commands, cubes, consts, entity)
+    }
+}
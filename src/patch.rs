@@ -0,0 +1,661 @@
+use super::*;
+use document::Value;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use unreal_asset::{
+    engine_version::EngineVersion,
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    reader::archive_trait::ArchiveTrait,
+    types::PackageIndex,
+};
+
+// edits recorded against object names and property keys rather than export indices
+// so they can be replayed onto a fresh copy of the map the patch was made for
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum Op {
+    // name is what the new actor was called when recorded so later ops can refer to it
+    Duplicate {
+        actor: String,
+        name: String,
+    },
+    Transplant {
+        from: std::path::PathBuf,
+        // the version the donor was read as when it isn't the map's
+        #[serde(default)]
+        version: Option<String>,
+        actor: String,
+        name: String,
+    },
+    Delete {
+        actor: String,
+    },
+    // references to an actor moved over to its replacement
+    Retarget {
+        actor: String,
+        to: String,
+    },
+    // component is none for properties on the actor itself
+    Set {
+        actor: String,
+        component: Option<String>,
+        property: String,
+        value: Value,
+    },
+    // the world transform in the editor's space
+    Transform {
+        actor: String,
+        translation: [f32; 3],
+        rotation: [f32; 4],
+        scale: [f32; 3],
+    },
+}
+
+impl Op {
+    fn actor(&self) -> &str {
+        match self {
+            Op::Duplicate { actor, .. }
+            | Op::Transplant { actor, .. }
+            | Op::Delete { actor }
+            | Op::Retarget { actor, .. }
+            | Op::Set { actor, .. }
+            | Op::Transform { actor, .. } => actor,
+        }
+    }
+}
+
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Op::Duplicate { actor, name } => write!(f, "+ {name} duplicated from {actor}"),
+            Op::Transplant {
+                from, actor, name, ..
+            } => write!(
+                f,
+                "+ {name} transplanted from {actor} in {}",
+                from.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            Op::Delete { actor } => write!(f, "- {actor}"),
+            Op::Retarget { actor, to } => write!(f, "~ {actor} references moved to {to}"),
+            Op::Set {
+                actor,
+                component: Some(component),
+                property,
+                ..
+            } => write!(f, "~ {actor}.{component}.{property}"),
+            Op::Set {
+                actor, property, ..
+            } => write!(f, "~ {actor}.{property}"),
+            Op::Transform { actor, .. } => write!(f, "~ {actor} transform"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct Patch {
+    pub ops: Vec<Op>,
+}
+
+#[derive(Default, Resource)]
+pub struct Recorder {
+    pub open: bool,
+    pub recording: bool,
+    pub patch: Patch,
+}
+
+impl Recorder {
+    /// adds an op merging it with the last one if it's a later value of the same thing
+    pub fn push(&mut self, op: Op) {
+        if !self.recording {
+            return;
+        }
+        let same = match (self.patch.ops.last(), &op) {
+            (
+                Some(Op::Set {
+                    actor,
+                    component,
+                    property,
+                    ..
+                }),
+                Op::Set {
+                    actor: new,
+                    component: new_component,
+                    property: new_property,
+                    ..
+                },
+            ) => actor == new && component == new_component && property == new_property,
+            (Some(Op::Transform { actor, .. }), Op::Transform { actor: new, .. }) => actor == new,
+            _ => false,
+        };
+        if same {
+            self.patch.ops.pop();
+        }
+        self.patch.ops.push(op)
+    }
+}
+
+/// the full object name of an export which is what ops refer to
+pub fn name(map: &Asset, export: usize) -> String {
+    actor::full_name(&map.asset_data.exports[export].get_base_export().object_name)
+}
+
+/// the names of an object and its outers which tell it apart from same named components
+fn path(map: &Asset, index: PackageIndex) -> Option<String> {
+    let (name, outer) = match index.index {
+        i if i > 0 => {
+            let ex = map.get_export(index)?.get_base_export();
+            (actor::full_name(&ex.object_name), ex.outer_index)
+        }
+        _ => {
+            let imp = map.get_import(index)?;
+            (actor::full_name(&imp.object_name), imp.outer_index)
+        }
+    };
+    match outer.index {
+        0 => Some(name),
+        _ => Some(path(map, outer)? + "." + &name),
+    }
+}
+
+/// indices differ between maps so references are recorded by path
+fn stable(map: &Asset, value: Value) -> Value {
+    match value {
+        Value::Object(index) if index != 0 => match path(map, PackageIndex::new(index)) {
+            Some(path) => Value::Reference(path),
+            None => Value::Opaque("ObjectProperty".into()),
+        },
+        Value::Struct(props) => Value::Struct(
+            props
+                .into_iter()
+                .map(|prop| document::Prop {
+                    name: prop.name,
+                    value: stable(map, prop.value),
+                })
+                .collect(),
+        ),
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(|value| stable(map, value)).collect())
+        }
+        value => value,
+    }
+}
+
+/// finds what recorded references point to in the map being patched
+fn resolve(map: &Asset, value: &Value) -> Result<Value, String> {
+    Ok(match value {
+        Value::Reference(recorded) => {
+            let exports = (1..=map.asset_data.exports.len() as i32).map(PackageIndex::new);
+            let imports = (1..=map.imports.len() as i32).map(|i| PackageIndex::new(-i));
+            match exports
+                .chain(imports)
+                .find(|index| path(map, *index).as_ref() == Some(recorded))
+            {
+                Some(index) => Value::Object(index.index),
+                None => return Err(format!("{recorded} isn't in the map")),
+            }
+        }
+        Value::Struct(props) => Value::Struct(
+            props
+                .iter()
+                .map(|prop| {
+                    Ok(document::Prop {
+                        name: prop.name.clone(),
+                        value: resolve(map, &prop.value)?,
+                    })
+                })
+                .collect::<Result<_, String>>()?,
+        ),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|value| resolve(map, value))
+                .collect::<Result<_, _>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
+/// the top-level property values of an actor and its components
+pub fn snapshot(map: &Asset, actor: &actor::Actor) -> BTreeMap<(Option<String>, String), Value> {
+    let mut out = BTreeMap::new();
+    let mut exports = actor.components(map);
+    exports.insert(0, actor.export);
+    for export in exports {
+        let Some(norm) = map.asset_data.exports[export].get_normal_export() else {
+            continue;
+        };
+        let component = (export != actor.export).then(|| name(map, export));
+        for prop in norm.properties.iter() {
+            out.insert(
+                (component.clone(), diff::key(prop)),
+                stable(map, document::value(prop)),
+            );
+        }
+    }
+    out
+}
+
+/// the properties which have changed since a snapshot as ops
+/// and the ones which were added since replaying can't create them
+pub fn changes(
+    map: &Asset,
+    actor: &actor::Actor,
+    before: &BTreeMap<(Option<String>, String), Value>,
+) -> (Vec<Op>, Vec<String>) {
+    let name = name(map, actor.export);
+    let mut ops = Vec::new();
+    let mut added = Vec::new();
+    for ((component, property), value) in snapshot(map, actor) {
+        match before.get(&(component.clone(), property.clone())) {
+            Some(old) if old != &value => ops.push(Op::Set {
+                actor: name.clone(),
+                component,
+                property,
+                value,
+            }),
+            Some(_) => (),
+            // transforms are recorded as their own ops
+            None if [actor::LOCATION, actor::ROTATION, actor::SCALE]
+                .contains(&property.as_str()) => {}
+            None => added.push(match component {
+                Some(component) => format!("{component}.{property}"),
+                None => property,
+            }),
+        }
+    }
+    (ops, added)
+}
+
+pub fn transform(map: &Asset, actor: &actor::Actor, transform: &Transform) -> Op {
+    Op::Transform {
+        actor: name(map, actor.export),
+        translation: transform.translation.to_array(),
+        rotation: transform.rotation.to_array(),
+        scale: transform.scale.to_array(),
+    }
+}
+
+fn find(map: &Asset, name: &str) -> Option<actor::Actor> {
+    actor::get_actors(map)
+        .into_iter()
+        .find(|index| self::name(map, index.index as usize - 1) == name)
+        .and_then(|index| actor::Actor::new(map, index).ok())
+        .map(|(_, actor)| actor)
+}
+
+/// applies a patch returning the ops which couldn't be
+pub fn replay(map: &mut Asset, patch: &Patch, version: EngineVersion) -> Vec<String> {
+    let mut problems = Vec::new();
+    // recorded names of added actors to what they ended up being called here
+    let mut renames: HashMap<String, String> = HashMap::new();
+    let mut donors: HashMap<std::path::PathBuf, Asset> = HashMap::new();
+    for op in patch.ops.iter() {
+        let recorded = op.actor();
+        let resolved = renames
+            .get(recorded)
+            .cloned()
+            .unwrap_or_else(|| recorded.to_string());
+        // donors are looked up in their own map
+        let found = match op {
+            Op::Transplant { .. } => None,
+            _ => match find(map, &resolved) {
+                Some(actor) => Some(actor),
+                None => {
+                    problems.push(format!("{op}: {resolved} isn't in the map"));
+                    continue;
+                }
+            },
+        };
+        match (op, found) {
+            (Op::Duplicate { name, .. }, Some(actor)) => {
+                let len = map.asset_data.exports.len();
+                actor.duplicate(map, &mut Vec::new());
+                renames.insert(name.clone(), self::name(map, len));
+            }
+            (
+                Op::Transplant {
+                    from,
+                    version: donor_version,
+                    actor,
+                    name,
+                },
+                _,
+            ) => {
+                if !donors.contains_key(from) {
+                    let donor_version = donor_version
+                        .as_ref()
+                        .and_then(|name| VERSIONS.iter().find(|(_, ver)| *ver == name.as_str()))
+                        .map_or(version, |(ver, _)| *ver);
+                    // prefabs hold their own version
+                    let donor = match from.extension().is_some_and(|ext| ext == "ron") {
+                        true => std::fs::read_to_string(from)
+                            .map_err(|e| e.to_string())
+                            .and_then(|text| clipboard::decode(&text)),
+                        false => asset::open(from, donor_version).map_err(|e| e.to_string()),
+                    };
+                    match donor {
+                        Ok(donor) => {
+                            donors.insert(from.clone(), donor);
+                        }
+                        Err(e) => {
+                            problems.push(format!("{op}: {e}"));
+                            continue;
+                        }
+                    }
+                }
+                let donor = &donors[from];
+                let Some(actor) = find(donor, actor) else {
+                    problems.push(format!("{op}: {actor} isn't in the donor"));
+                    continue;
+                };
+                let len = map.asset_data.exports.len();
+                if let Err(e) = actor.transplant(map, donor, &mut Vec::new(), &mut Vec::new()) {
+                    problems.push(format!("{op}: {}", e.join(", ")));
+                    continue;
+                }
+                renames.insert(name.clone(), self::name(map, len));
+            }
+            (Op::Delete { .. }, Some(actor)) => {
+                actor::unlink(map, &[&actor]);
+                actor.delete(map)
+            }
+            (Op::Retarget { to, .. }, Some(actor)) => {
+                let to = renames.get(to).unwrap_or(to);
+                match find(map, to) {
+                    Some(replacement) => actor::retarget(map, &actor, &replacement),
+                    None => problems.push(format!("{op}: {to} isn't in the map")),
+                }
+            }
+            (
+                Op::Set {
+                    component,
+                    property,
+                    value,
+                    ..
+                },
+                Some(actor),
+            ) => {
+                let export = match component {
+                    Some(component) => match actor
+                        .components(map)
+                        .into_iter()
+                        .find(|export| &self::name(map, *export) == component)
+                    {
+                        Some(export) => export,
+                        None => {
+                            problems.push(format!("{op}: {component} isn't on {resolved}"));
+                            continue;
+                        }
+                    },
+                    None => actor.export,
+                };
+                let value = match resolve(map, value) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        problems.push(format!("{op}: {e}"));
+                        continue;
+                    }
+                };
+                let names = map.get_name_map();
+                let Some(prop) = map.asset_data.exports[export]
+                    .get_normal_export_mut()
+                    .and_then(|norm| {
+                        norm.properties
+                            .iter_mut()
+                            .find(|prop| &diff::key(prop) == property)
+                    })
+                else {
+                    problems.push(format!("{op}: {property} isn't set on {resolved}"));
+                    continue;
+                };
+                if let Err(e) = document::apply(&names, prop, &value, property) {
+                    problems.push(format!("{op}: {e}"))
+                }
+            }
+            (
+                Op::Transform {
+                    translation,
+                    rotation,
+                    scale,
+                    ..
+                },
+                Some(actor),
+            ) => {
+                let current = actor.transform(map);
+                actor.add_location(map, Vec3::from_array(*translation) - current.translation);
+                actor.combine_rotation(
+                    map,
+                    Quat::from_array(*rotation) * current.rotation.inverse(),
+                );
+                // a zero scale can't be multiplied back out
+                if !current.scale.cmpeq(Vec3::ZERO).any() {
+                    actor.mul_scale(map, Vec3::from_array(*scale) / current.scale);
+                }
+            }
+            _ => (),
+        }
+    }
+    problems
+}
+
+#[test]
+fn recording() {
+    let mut map = regression::sample();
+    let actor = regression::stand_ins(&mut map).remove(0);
+    let before = snapshot(&map, &actor);
+    let mut names = map.get_name_map();
+    let norm = map.asset_data.exports[actor.export]
+        .get_normal_export_mut()
+        .unwrap();
+    props::set_vector(
+        &mut norm.properties,
+        actor::LOCATION,
+        bevy::math::DVec3::splat(500.0),
+        &mut names,
+    );
+    let (ops, added) = changes(&map, &actor, &before);
+    assert_eq!(ops.len(), 1);
+    assert!(added.is_empty());
+    let Op::Set {
+        component,
+        property,
+        ..
+    } = &ops[0]
+    else {
+        panic!("{} isn't a set", ops[0])
+    };
+    assert!(component.is_none() && property == actor::LOCATION);
+    // new properties can't be replayed so they're reported instead
+    let norm = map.asset_data.exports[actor.export]
+        .get_normal_export_mut()
+        .unwrap();
+    props::set_float(&mut norm.properties, "Intensity", 8.0, &mut names);
+    let (_, added) = changes(&map, &actor, &before);
+    assert_eq!(added, ["Intensity"]);
+    // later values of the same property replace the earlier
+    let mut recorder = Recorder {
+        recording: true,
+        ..Default::default()
+    };
+    recorder.push(ops[0].clone());
+    recorder.push(ops[0].clone());
+    recorder.push(transform(&map, &actor, &Transform::IDENTITY));
+    assert_eq!(recorder.patch.ops.len(), 2);
+    let text =
+        ron::ser::to_string_pretty(&recorder.patch, ron::ser::PrettyConfig::default()).unwrap();
+    let patch: Patch = ron::from_str(&text).unwrap();
+    assert_eq!(
+        patch.ops.iter().map(Op::to_string).collect::<Vec<_>>(),
+        recorder
+            .patch
+            .ops
+            .iter()
+            .map(Op::to_string)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn references() {
+    let map = regression::sample();
+    // an export and an import go by path and come back to the same index
+    for index in [1, -1] {
+        let value = stable(&map, Value::Object(index));
+        assert!(matches!(value, Value::Reference(_)));
+        assert!(resolve(&map, &value) == Ok(Value::Object(index)));
+    }
+    assert!(resolve(&map, &Value::Reference("Nowhere".into())).is_err());
+}
+
+/// applies a patch for stove patch <version> <map> <patch> <out>
+pub fn cli() -> AppExit {
+    let args: Vec<_> = std::env::args().skip(2).collect();
+    let [version, path, patch, out] = args.as_slice() else {
+        eprintln!("usage: stove patch <version e.g 4.27> <map.umap> <patch.ron> <out.umap>");
+        return AppExit::error();
+    };
+    let Some(version) = engine(version) else {
+        return AppExit::error();
+    };
+    let result = asset::open(path, version)
+        .map_err(|e| e.to_string())
+        .and_then(|map| {
+            let patch = std::fs::read_to_string(patch).map_err(|e| e.to_string())?;
+            Ok((
+                map,
+                ron::from_str::<Patch>(&patch).map_err(|e| e.to_string())?,
+            ))
+        })
+        .and_then(|(mut map, patch)| {
+            let problems = replay(&mut map, &patch, version);
+            if !problems.is_empty() {
+                return Err(problems.join("\n") + "\nso nothing was saved");
+            }
+            asset::save(&mut map, out).map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(()) => AppExit::Success,
+        Err(e) => {
+            eprintln!("{e}");
+            AppExit::error()
+        }
+    }
+}
+
+pub fn record(trigger: Trigger<triggers::Record>, mut recorder: ResMut<Recorder>) {
+    recorder.push(trigger.event().0.clone())
+}
+
+pub fn toggle(_: Trigger<triggers::Patch>, mut recorder: ResMut<Recorder>) {
+    recorder.open = !recorder.open
+}
+
+/// records where selected actors have been moved to
+pub fn transforms(
+    mut recorder: ResMut<Recorder>,
+    map: NonSend<Map>,
+    // attached children follow on their own so only what's been moved directly counts
+    moved: Query<(Entity, &actor::Actor, Ref<Transform>), With<actor::Selected>>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    if !recorder.recording {
+        return;
+    }
+    // including ones selected along with what they're attached to
+    let followers = attachment::followers(
+        map,
+        &moved
+            .iter()
+            .map(|(entity, actor, _)| (entity, actor))
+            .collect::<Vec<_>>(),
+    );
+    for (entity, actor, moved) in moved.iter() {
+        if moved.is_changed() && !moved.is_added() && !followers.contains(&entity) {
+            recorder.push(transform(map, actor, &moved))
+        }
+    }
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    mut recorder: ResMut<Recorder>,
+) {
+    if !recorder.open {
+        return;
+    }
+    let mut open = true;
+    let mut remove = None;
+    let recorder = recorder.as_mut();
+    egui::Window::new("patch")
+        .open(&mut open)
+        .default_width(350.0)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.toggle_value(&mut recorder.recording, "⏺ record");
+                if ui
+                    .button("apply")
+                    .on_hover_text("replay a patch onto this map")
+                    .clicked()
+                {
+                    commands.trigger(triggers::ApplyPatch)
+                }
+                if ui.button("save").clicked() {
+                    if let Some(path) = rfd::FileDialog::new()
+                        .set_title("save patch")
+                        .add_filter("ron", &["ron"])
+                        .save_file()
+                    {
+                        match ron::ser::to_string_pretty(
+                            &recorder.patch,
+                            ron::ser::PrettyConfig::default(),
+                        )
+                        .map_err(|e| e.to_string())
+                        .and_then(|text| std::fs::write(path, text).map_err(|e| e.to_string()))
+                        {
+                            Ok(_) => notif.send(Notif {
+                                message: "patch saved".into(),
+                                kind: Success,
+                            }),
+                            Err(e) => notif.send(Notif {
+                                message: e,
+                                kind: Error,
+                            }),
+                        };
+                    }
+                }
+                if ui.button("clear").clicked() {
+                    recorder.patch.ops.clear()
+                }
+            });
+            if recorder.patch.ops.is_empty() {
+                ui.label(match recorder.recording {
+                    true => "edits will show up here",
+                    false => "nothing recorded",
+                });
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .show_rows(
+                    ui,
+                    ui.text_style_height(&egui::TextStyle::Body),
+                    recorder.patch.ops.len(),
+                    |ui, range| {
+                        for (i, op) in range.clone().zip(&recorder.patch.ops[range]) {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("x").clicked() {
+                                    remove = Some(i)
+                                }
+                                ui.label(op.to_string());
+                            });
+                        }
+                    },
+                );
+        });
+    if let Some(i) = remove {
+        recorder.patch.ops.remove(i);
+    }
+    recorder.open &= open;
+}
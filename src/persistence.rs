@@ -40,6 +40,7 @@ pub fn load(
         retrieve(&mut appdata.script, "script", data);
         retrieve(&mut appdata.cap, "cap", data);
         retrieve(&mut appdata.rate, "rate", data);
+        retrieve(&mut appdata.placement, "placement", data);
         retrieve(&mut fullscreen, "fullscreen", data);
     });
     fps.limiter = match appdata.cap {
@@ -71,6 +72,7 @@ pub fn write(mut ctx: bevy_egui::EguiContexts, appdata: Res<AppData>, windows: Q
         storage.insert_persisted(Id::new("script"), appdata.script.clone());
         storage.insert_persisted(Id::new("cap"), appdata.cap);
         storage.insert_persisted(Id::new("rate"), appdata.rate);
+        storage.insert_persisted(Id::new("placement"), appdata.placement);
         storage.insert_persisted(
             Id::new("fullscreen"),
             windows
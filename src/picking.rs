@@ -10,19 +10,44 @@ pub fn pick(
     camera: Query<&bevy_mod_raycast::deferred::RaycastSource<()>>,
     selected: Query<(Entity, &Transform), With<actor::Selected>>,
     parents: Query<&Parent>,
+    points: Query<(&spline::Point, &Parent)>,
     mut cubes: Query<&mut Handle<wire::Wire>>,
+    library: Res<prefab::Library>,
+    scatter: Res<scatter::Scatter>,
     mut ctx: bevy_egui::EguiContexts,
 ) {
     // EguiContexts isn't a ReadOnlySystemParam so can't make into a conditional
     if ctx.ctx_mut().is_pointer_over_area() {
         return;
     }
+    // the click places a prefab or scatters instead
+    if library.placing() || scatter.brushing() {
+        return;
+    }
     if mouse.any_just_released([MouseButton::Left, MouseButton::Middle, MouseButton::Right])
         || keys.any_just_released([KeyCode::ShiftLeft, KeyCode::ShiftRight])
     {
         *drag = Drag::None
     }
     if let Some((entity, data)) = camera.single().get_nearest_intersection() {
+        // spline point handles take priority over their actors
+        if let Some((point, curve)) = parents
+            .get(entity)
+            .ok()
+            .and_then(|parent| points.get(parent.get()).ok())
+            .filter(|_| mouse.just_pressed(MouseButton::Left))
+        {
+            let curve = curve.get();
+            if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+                commands.trigger(triggers::DeletePoint(curve, point.0));
+            } else if keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
+                commands.trigger(triggers::InsertPoint(curve, point.0));
+                *drag = Drag::Point(curve, point.0 + 1, data.position())
+            } else {
+                *drag = Drag::Point(curve, point.0, data.position())
+            }
+            return;
+        }
         if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
             && (selected.contains(entity)
                 || parents
@@ -32,7 +57,8 @@ pub fn pick(
             match &mouse {
                 mouse if mouse.just_pressed(MouseButton::Left) => {
                     if keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]) {
-                        commands.trigger(triggers::Duplicate);
+                        // the copies are dragged from where the originals are
+                        commands.trigger(triggers::Duplicate(false));
                     }
                     *drag = Drag::Translate(data.position())
                 }
@@ -49,12 +75,18 @@ pub fn pick(
             }
         } else if mouse.just_pressed(MouseButton::Left) {
             match parents.get(entity) {
-                Ok(parent) => {
-                    if let Ok(mut mat) = cubes.get_mut(parent.get()) {
+                Ok(parent) => match cubes.get_mut(parent.get()) {
+                    Ok(mut mat) => {
                         commands.entity(parent.get()).insert(actor::Selected);
                         *mat = consts.selected.clone_weak();
                     }
-                }
+                    // any mesh piece selects the actor it belongs to
+                    Err(_) => {
+                        commands
+                            .entity(parent.get())
+                            .insert(actor::SelectedBundle::default());
+                    }
+                },
                 Err(_) => {
                     commands
                         .entity(entity)
@@ -86,7 +118,38 @@ pub fn pick(
     }
 }
 
+/// gets where the cursor hits the locked plane through the previous position
+fn hit(
+    camera: &(
+        &bevy_mod_raycast::deferred::RaycastSource<()>,
+        &smooth_bevy_cameras::LookTransform,
+    ),
+    lock: &Lock,
+    pos: Vec3,
+) -> Option<(Vec3, Vec3)> {
+    let ray = camera.0.ray?;
+    let dist = ray.intersect_plane(
+        pos,
+        InfinitePlane3d::new(match lock {
+            Lock::XYZ => camera.1.look_direction().unwrap_or_default(),
+            Lock::XY | Lock::X => Vec3::Z,
+            Lock::YZ | Lock::Y => Vec3::X,
+            Lock::ZX | Lock::Z => Vec3::Y,
+        }),
+    )?;
+    let hit = ray.origin + ray.direction * dist;
+    let mut offset = hit - pos;
+    match lock {
+        Lock::X => offset.y = 0.0,
+        Lock::Y => offset.z = 0.0,
+        Lock::Z => offset.x = 0.0,
+        _ => (),
+    }
+    Some((hit, offset))
+}
+
 pub fn drag(
+    mut commands: Commands,
     mut drag: ResMut<Drag>,
     lock: Res<Lock>,
     window: Query<&Window, With<bevy::window::PrimaryWindow>>,
@@ -95,40 +158,53 @@ pub fn drag(
         &bevy_mod_raycast::deferred::RaycastSource<()>,
         &smooth_bevy_cameras::LookTransform,
     )>,
-    mut selected: Query<(&actor::Actor, &mut Transform), With<actor::Selected>>,
+    mut selected: Query<(Entity, &actor::Actor, &mut Transform), With<actor::Selected>>,
+    curves: Query<(&spline::Curve, &GlobalTransform)>,
+    volumes: Query<&volume::Volume>,
 ) {
     let Some((map, ..)) = &mut map.0 else { return };
     let window = window.single();
     let camera = camera.single();
+    // children of other selected actors would be moved twice
+    let followers = attachment::followers(
+        map,
+        &selected
+            .iter()
+            .map(|(entity, actor, _)| (entity, actor))
+            .collect::<Vec<_>>(),
+    );
+    let mut selected = selected
+        .iter_mut()
+        .filter(|(entity, ..)| !followers.contains(entity));
     match drag.as_mut() {
         Drag::None => (),
         Drag::Translate(pos) => {
-            let Some(ray) = camera.0.ray else { return };
-            let Some(dist) = ray.intersect_plane(
-                *pos,
-                InfinitePlane3d::new(match lock.as_ref() {
-                    Lock::XYZ => camera.1.look_direction().unwrap_or_default(),
-                    Lock::XY | Lock::X => Vec3::Z,
-                    Lock::YZ | Lock::Y => Vec3::X,
-                    Lock::ZX | Lock::Z => Vec3::Y,
-                }),
-            ) else {
+            let Some((hit, offset)) = hit(&camera, &lock, *pos) else {
                 return;
             };
-            let hit = ray.origin + ray.direction * dist;
-            let mut offset = hit - *pos;
-            for (actor, mut transform) in selected.iter_mut() {
-                match lock.as_ref() {
-                    Lock::X => offset.y = 0.0,
-                    Lock::Y => offset.z = 0.0,
-                    Lock::Z => offset.x = 0.0,
-                    _ => (),
-                }
+            for (_, actor, mut transform) in selected {
                 actor.add_location(map, offset);
                 transform.translation += offset;
             }
             *drag = Drag::Translate(hit);
         }
+        Drag::Point(curve, index, pos) => {
+            let Some((hit, offset)) = hit(&camera, &lock, *pos) else {
+                return;
+            };
+            let Ok((comp, global)) = curves.get(*curve) else {
+                return;
+            };
+            // points are stored relative to their component
+            spline::translate(
+                map,
+                comp.0,
+                *index,
+                global.affine().inverse().transform_vector3(offset),
+            );
+            commands.trigger(triggers::RefreshSpline(*curve));
+            *pos = hit;
+        }
         Drag::Rotate(start, prev) => {
             let current =
                 (window.cursor_position().unwrap_or_default() - *start).normalize_or_zero();
@@ -147,7 +223,7 @@ pub fn drag(
                 },
                 angle,
             );
-            for (actor, mut transform) in selected.iter_mut() {
+            for (_, actor, mut transform) in selected {
                 actor.combine_rotation(map, rotation);
                 transform.rotation = rotation * transform.rotation;
             }
@@ -166,9 +242,17 @@ pub fn drag(
                 Lock::Y => Vec3::new(1.0, factor, 1.0),
                 Lock::Z => Vec3::new(1.0, 1.0, factor),
             };
-            for (actor, mut transform) in selected.iter_mut() {
-                actor.mul_scale(map, scalar);
-                transform.scale *= scalar;
+            for (entity, actor, mut transform) in selected {
+                match volumes.get(entity) {
+                    // resize the volume itself rather than stretching it
+                    Ok(volume) if volume::resize(map, volume.0, scalar) => {
+                        commands.trigger(triggers::RefreshVolume(entity))
+                    }
+                    _ => {
+                        actor.mul_scale(map, scalar);
+                        transform.scale = actor.transform(map).scale;
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,310 @@
+use super::*;
+use std::path::PathBuf;
+
+// groups of actors kept in the config folder to stamp into any map
+// they're stored like copied actors but around the centre of the selection
+
+fn folder() -> Option<PathBuf> {
+    config().map(|path| path.join("prefabs"))
+}
+
+#[derive(Default, Resource)]
+pub struct Library {
+    pub open: bool,
+    prefabs: Vec<PathBuf>,
+    // the prefab which will be placed on the next click in the viewport
+    armed: Option<PathBuf>,
+    name: String,
+}
+
+/// the saved prefabs in name order
+pub fn saved() -> Vec<PathBuf> {
+    let mut prefabs: Vec<_> = folder()
+        .and_then(|folder| std::fs::read_dir(folder).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .collect();
+    prefabs.sort();
+    prefabs
+}
+
+impl Library {
+    fn refresh(&mut self) {
+        self.prefabs = saved();
+    }
+
+    pub fn placing(&self) -> bool {
+        self.armed.is_some()
+    }
+
+    /// stops placing so the prefab doesn't land in another map
+    pub fn disarm(&mut self) {
+        self.armed = None
+    }
+}
+
+pub fn toggle(_: Trigger<triggers::Prefabs>, mut library: ResMut<Library>) {
+    library.open = !library.open;
+    if library.open {
+        library.refresh()
+    }
+}
+
+/// the file a prefab is saved to with anything which could leave the folder replaced
+fn file(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    format!("{name}.ron")
+}
+
+#[test]
+fn files() {
+    assert_eq!(file("lamp.v2"), "lamp.v2.ron");
+    assert_eq!(file("../lamp"), ".._lamp.ron");
+    assert_eq!(file("a\\b/c"), "a_b_c.ron");
+}
+
+pub fn name(path: &std::path::Path) -> String {
+    path.file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut notif: EventWriter<Notif>,
+    mut library: ResMut<Library>,
+    appdata: Res<AppData>,
+    mut map: NonSendMut<Map>,
+    selected: Query<(&actor::Actor, &Transform), With<actor::Selected>>,
+) {
+    if !library.open {
+        library.armed = None;
+        return;
+    }
+    let mut open = true;
+    let mut save = false;
+    let mut remove = None;
+    let library = library.as_mut();
+    egui::Window::new("prefabs")
+        .open(&mut open)
+        .default_width(250.0)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut library.name)
+                        .hint_text("name")
+                        .desired_width(120.0),
+                );
+                ui.add_enabled_ui(
+                    !selected.is_empty() && !library.name.trim().is_empty(),
+                    |ui| {
+                        if ui
+                            .button("save")
+                            .on_hover_text("save the selection as a prefab")
+                            .clicked()
+                        {
+                            save = true
+                        }
+                    },
+                );
+                if ui.button("⟳").on_hover_text("refresh").clicked() {
+                    library.refresh()
+                }
+            });
+            match &library.armed {
+                Some(armed) => ui.label(format!("click to place {}", name(armed))),
+                None => ui.weak("pick a prefab then click in the viewport"),
+            };
+            if library.prefabs.is_empty() {
+                ui.label("no prefabs saved yet");
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for (i, path) in library.prefabs.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("x").clicked() {
+                                remove = Some(i)
+                            }
+                            let armed = library.armed.as_ref() == Some(path);
+                            if ui.selectable_label(armed, name(path)).clicked() {
+                                library.armed = match armed {
+                                    true => None,
+                                    false => Some(path.clone()),
+                                }
+                            }
+                        });
+                    }
+                });
+        });
+    if let Some(i) = remove {
+        let path = library.prefabs.remove(i);
+        if library.armed.as_ref() == Some(&path) {
+            library.armed = None
+        }
+        if let Err(e) = std::fs::remove_file(path) {
+            notif.send(Notif {
+                message: e.to_string(),
+                kind: Error,
+            });
+        }
+    }
+    library.open &= open;
+    if save {
+        let Some((map, ..)) = &mut map.0 else {
+            notif.send(Notif {
+                message: "no map to save a prefab from".into(),
+                kind: Error,
+            });
+            return;
+        };
+        let origin = selected
+            .iter()
+            .fold(Vec3::ZERO, |pos, (_, trans)| pos + trans.translation)
+            / selected.iter().len() as f32;
+        let actors: Vec<_> = selected.iter().map(|(actor, _)| actor).collect();
+        let result = folder()
+            .ok_or_else(|| "there's no config folder to keep prefabs in".to_string())
+            .and_then(|folder| {
+                std::fs::create_dir_all(&folder).map_err(|e| e.to_string())?;
+                let text = clipboard::encode(map, &actors, appdata.version, origin)?;
+                std::fs::write(folder.join(file(&library.name)), text).map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => {
+                notif.send(Notif {
+                    message: format!("saved {} as a prefab", library.name),
+                    kind: Success,
+                });
+                library.name.clear();
+                library.refresh();
+            }
+            Err(e) => {
+                notif.send(Notif {
+                    message: e,
+                    kind: Error,
+                });
+            }
+        }
+    }
+}
+
+/// places the armed prefab where the viewport is clicked
+pub fn place(
+    mut commands: Commands,
+    mut library: ResMut<Library>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    camera: Query<(
+        &bevy_mod_raycast::deferred::RaycastSource<()>,
+        &smooth_bevy_cameras::LookTransform,
+    )>,
+    mut ctx: bevy_egui::EguiContexts,
+) {
+    if library.armed.is_none() {
+        return;
+    }
+    if keys.just_released(KeyCode::Escape) {
+        library.armed = None;
+        return;
+    }
+    if ctx.ctx_mut().is_pointer_over_area() || !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(path) = library.armed.take() else {
+        return;
+    };
+    let (source, look) = camera.single();
+    if let Some(position) = action::target(Placement::Cursor, source, look) {
+        commands.trigger(triggers::PlacePrefab(path, position))
+    }
+}
+
+pub fn spawn(
+    trigger: Trigger<triggers::PlacePrefab>,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    mut map: NonSendMut<Map>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+    mut tabs: NonSendMut<tabs::Tabs>,
+) {
+    let triggers::PlacePrefab(path, position) = trigger.event();
+    let Some((map, _, export_names, import_names)) = &mut map.0 else {
+        notif.send(Notif {
+            message: "no map to place the prefab in".into(),
+            kind: Error,
+        });
+        return;
+    };
+    let donor = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| clipboard::decode(&text))
+    {
+        Ok(donor) => donor,
+        Err(e) => {
+            notif.send(Notif {
+                message: e,
+                kind: Error,
+            });
+            return;
+        }
+    };
+    for entity in selected.iter() {
+        outliner::deselect(&mut commands, &mut cubes, &consts, entity);
+    }
+    let (batch, problems) = clipboard::place(
+        &mut commands,
+        map,
+        export_names,
+        import_names,
+        &donor,
+        Some(path),
+        *position,
+    );
+    for problem in problems {
+        notif.send(Notif {
+            message: format!("couldn't place {problem}"),
+            kind: Error,
+        });
+    }
+    let placed = batch.len();
+    dialog::spawn_new(
+        map,
+        batch,
+        &mut commands,
+        &mut registry,
+        &content,
+        &appdata,
+        &consts,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+    tabs.edit();
+    notif.send(Notif {
+        message: format!("placed {} with {placed} actors", name(path)),
+        kind: Success,
+    });
+}
@@ -0,0 +1,303 @@
+use super::*;
+use bevy::render::{camera::RenderTarget, render_resource::*, view::RenderLayers};
+
+// actors picked in the transplant window are spawned on their own render layer
+// where a second camera draws them into an image shown next to what they'd add
+
+const LAYER: RenderLayers = RenderLayers::layer(1);
+const SIZE: u32 = 320;
+
+#[derive(Component)]
+pub struct Shown;
+
+#[derive(Resource)]
+pub struct Preview {
+    image: Handle<Image>,
+    camera: Entity,
+    // donor exports which are currently spawned
+    shown: Vec<usize>,
+    reports: Vec<(String, actor::Report)>,
+    // the camera orbits the centre of the shown actors
+    centre: Vec3,
+    radius: f32,
+    yaw: f32,
+    pitch: f32,
+}
+
+impl Preview {
+    fn eye(&self) -> Transform {
+        let offset = Quat::from_euler(EulerRot::YXZ, self.yaw, -self.pitch, 0.0) * Vec3::Z;
+        Transform::from_translation(self.centre + offset * self.radius * 2.0)
+            .looking_at(self.centre, Vec3::Y)
+    }
+}
+
+pub fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = Extent3d {
+        width: SIZE,
+        height: SIZE,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+    let camera = commands
+        .spawn((
+            Camera3dBundle {
+                camera: Camera {
+                    target: RenderTarget::Image(image.clone()),
+                    // only rendered while there's something to show
+                    is_active: false,
+                    order: -1,
+                    ..default()
+                },
+                tonemapping: bevy::core_pipeline::tonemapping::Tonemapping::None,
+                ..default()
+            },
+            LAYER,
+        ))
+        .id();
+    commands.insert_resource(Preview {
+        image,
+        camera,
+        shown: Vec::new(),
+        reports: Vec::new(),
+        centre: Vec3::ZERO,
+        radius: 5.0,
+        yaw: 0.0,
+        pitch: 0.4,
+    });
+}
+
+/// respawns the preview whenever the actors picked for transplanting change
+pub fn sync(
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    mut preview: ResMut<Preview>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    transplant: NonSend<Transplant>,
+    map: NonSend<Map>,
+    shown: Query<Entity, With<Shown>>,
+    mut cameras: Query<(&mut Camera, &mut Transform)>,
+) {
+    let (donor, recipient, picked): (_, _, Vec<usize>) = match (&transplant.0, &map.0) {
+        (Some((donor, _, others, selected, _)), Some((recipient, ..))) => (
+            Some(donor),
+            Some(recipient),
+            selected.iter().map(|i| others[*i].export).collect(),
+        ),
+        _ => (None, None, Vec::new()),
+    };
+    if picked == preview.shown {
+        return;
+    }
+    for entity in shown.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    preview.reports.clear();
+    if let Ok((mut camera, _)) = cameras.get_mut(preview.camera) {
+        camera.is_active = !picked.is_empty()
+    }
+    preview.shown = picked;
+    let (Some(donor), Some(recipient)) = (donor, recipient) else {
+        return;
+    };
+    let mut batch: Vec<_> = preview
+        .shown
+        .iter()
+        .filter_map(|i| {
+            actor::Actor::new(donor, unreal_asset::types::PackageIndex::new(*i as i32 + 1)).ok()
+        })
+        .collect();
+    dialog::load(
+        donor,
+        &mut batch,
+        &mut registry,
+        &content,
+        &appdata,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+    let mut positions = Vec::with_capacity(batch.len());
+    for (pieces, actor) in batch {
+        let transform = actor.transform(donor);
+        positions.push(transform.translation);
+        preview
+            .reports
+            .push((actor.name.clone(), actor.report(recipient, donor)));
+        let mut entity = commands.spawn((
+            SpatialBundle {
+                transform,
+                ..default()
+            },
+            Shown,
+            LAYER,
+        ));
+        // like in the viewport actors without meshes are wire cubes
+        if !pieces
+            .iter()
+            .any(|piece| registry.meshes.contains_key(&piece.mesh))
+        {
+            entity.insert((consts.cube.clone_weak(), consts.unselected.clone_weak()));
+            continue;
+        }
+        entity.with_children(|parent| {
+            for piece in pieces.iter() {
+                let Some((sections, defaults)) = registry.meshes.get(&piece.mesh) else {
+                    continue;
+                };
+                for (mesh, slot) in sections {
+                    parent.spawn((
+                        MaterialMeshBundle {
+                            mesh: mesh.clone_weak(),
+                            material: actor::material(&registry, &consts, piece, defaults, *slot),
+                            transform: piece.transform,
+                            ..default()
+                        },
+                        LAYER,
+                    ));
+                }
+            }
+        });
+    }
+    preview.centre = positions.iter().sum::<Vec3>() / positions.len().max(1) as f32;
+    preview.radius = positions
+        .iter()
+        .map(|pos| pos.distance(preview.centre))
+        .fold(0.0, f32::max)
+        // leave room for the meshes themselves
+        + 5.0;
+    if let Ok((_, mut transform)) = cameras.get_mut(preview.camera) {
+        *transform = preview.eye()
+    }
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut preview: ResMut<Preview>,
+    hidden: Res<Hidden>,
+    transplant: NonSend<Transplant>,
+    mut cameras: Query<&mut Transform, With<Camera>>,
+) {
+    if hidden.0 || transplant.0.is_none() {
+        return;
+    }
+    let texture = ctx.add_image(preview.image.clone_weak());
+    let mut orbit = Vec2::ZERO;
+    egui::Window::new("transplant preview")
+        .anchor(egui::Align2::RIGHT_CENTER, (-10.0, 0.0))
+        .resizable(false)
+        .collapsible(false)
+        .show(ctx.ctx_mut(), |ui| {
+            if preview.shown.is_empty() {
+                ui.label("select actors to see what they'll bring");
+                return;
+            }
+            let response = ui
+                .add(
+                    egui::Image::new(egui::load::SizedTexture::new(
+                        texture,
+                        [SIZE as f32, SIZE as f32],
+                    ))
+                    .sense(egui::Sense::drag()),
+                )
+                .on_hover_text("drag to orbit");
+            if response.dragged() {
+                orbit = response.drag_delta()
+            }
+            let conflicts = preview
+                .reports
+                .iter()
+                .flat_map(|(_, report)| report.imports.iter())
+                .filter(|(_, status)| matches!(status, actor::Status::Conflict))
+                .count();
+            if conflicts != 0 {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!("{conflicts} imports clash with ones already in the map"),
+                );
+            }
+            let refused = preview
+                .reports
+                .iter()
+                .filter(|(_, report)| !report.problems.is_empty())
+                .count();
+            if refused != 0 {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("{refused} actors can't be converted from the donor's version"),
+                );
+            }
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for (i, (name, report)) in preview.reports.iter().enumerate() {
+                        egui::CollapsingHeader::new(name)
+                            .id_source(i)
+                            .show(ui, |ui| {
+                                for problem in report.problems.iter() {
+                                    ui.colored_label(ui.visuals().error_fg_color, problem);
+                                }
+                                ui.collapsing(format!("{} exports", report.exports.len()), |ui| {
+                                    for export in report.exports.iter() {
+                                        ui.label(export);
+                                    }
+                                });
+                                ui.collapsing(
+                                    format!("{} new imports", report.imports.len()),
+                                    |ui| {
+                                        for (import, status) in report.imports.iter() {
+                                            match status {
+                                                actor::Status::New => ui.label(import),
+                                                actor::Status::Conflict => ui
+                                                    .colored_label(
+                                                        ui.visuals().warn_fg_color,
+                                                        import,
+                                                    )
+                                                    .on_hover_text(
+                                                        "already imported under a different outer",
+                                                    ),
+                                            };
+                                        }
+                                    },
+                                );
+                                ui.collapsing(format!("{} new names", report.names.len()), |ui| {
+                                    for name in report.names.iter() {
+                                        ui.label(name);
+                                    }
+                                });
+                            });
+                    }
+                });
+        });
+    if orbit != Vec2::ZERO {
+        preview.yaw -= orbit.x * 0.01;
+        preview.pitch = (preview.pitch + orbit.y * 0.01).clamp(-1.5, 1.5);
+        if let Ok(mut transform) = cameras.get_mut(preview.camera) {
+            *transform = preview.eye()
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::math::{DVec3, Vec3};
+use unreal_asset::{
+    cast,
+    containers::{NameMap, SharedResource},
+    properties::{
+        int_property::FloatProperty, struct_property::StructProperty,
+        vector_property::VectorProperty, Property, PropertyDataTrait,
+    },
+    types::vector::Vector,
+    unversioned::ancestry::Ancestry,
+};
+
+/// converts unreal centimetres to bevy space
+pub fn to_bevy(pos: DVec3) -> Vec3 {
+    bevy::math::dvec3(pos.x, pos.z, pos.y).as_vec3() * 0.01
+}
+
+/// calls the function on every property nested inside this one
+pub fn visit(prop: &Property, func: &mut impl FnMut(&Property)) {
+    match prop {
+        Property::ArrayProperty(arr) => {
+            for entry in arr.value.iter() {
+                visit(entry, func);
+            }
+        }
+        Property::MapProperty(map) => {
+            for val in map.value.values() {
+                visit(val, func);
+            }
+        }
+        Property::SetProperty(set) => {
+            for entry in set.value.value.iter() {
+                visit(entry, func);
+            }
+        }
+        Property::StructProperty(struc) => {
+            for entry in struc.value.iter() {
+                visit(entry, func);
+            }
+        }
+        prop => func(prop),
+    }
+}
+
+pub fn find<'a>(props: &'a [Property], name: &str) -> Option<&'a Property> {
+    props.iter().find(|prop| prop.get_name() == name)
+}
+
+pub fn find_mut<'a>(props: &'a mut [Property], name: &str) -> Option<&'a mut Property> {
+    props.iter_mut().find(|prop| prop.get_name() == name)
+}
+
+/// vectors are either bare or wrapped in a struct depending on where they are
+pub fn as_vector(prop: &Property) -> Option<DVec3> {
+    match prop {
+        Property::VectorProperty(vec) => Some(vec),
+        Property::StructProperty(struc) => struc
+            .value
+            .first()
+            .and_then(|prop| cast!(Property, VectorProperty, prop)),
+        _ => None,
+    }
+    .map(|vec| bevy::math::dvec3(vec.value.x.0, vec.value.y.0, vec.value.z.0))
+}
+
+pub fn vector(props: &[Property], name: &str) -> Option<DVec3> {
+    find(props, name).and_then(as_vector)
+}
+
+pub fn float(props: &[Property], name: &str) -> Option<f32> {
+    find(props, name)
+        .and_then(|prop| cast!(Property, FloatProperty, prop))
+        .map(|float| float.value.0)
+}
+
+pub fn set_vector(
+    props: &mut Vec<Property>,
+    name: &str,
+    val: DVec3,
+    names: &mut SharedResource<NameMap>,
+) {
+    let value = Vector::new(val.x.into(), val.y.into(), val.z.into());
+    if let Some(Property::StructProperty(struc)) = find_mut(props, name) {
+        if let Some(Property::VectorProperty(vec)) = struc.value.first_mut() {
+            vec.value = value;
+            return;
+        }
+    }
+    // properties left as default are cut so add it back
+    if val == DVec3::ZERO {
+        return;
+    }
+    props.push(Property::StructProperty(StructProperty {
+        name: names.get_mut().add_fname(name),
+        ancestry: Ancestry {
+            ancestry: Vec::new(),
+        },
+        struct_type: Some(names.get_mut().add_fname("Vector")),
+        struct_guid: Some([0; 16].into()),
+        property_guid: None,
+        duplication_index: 0,
+        serialize_none: true,
+        value: vec![Property::VectorProperty(VectorProperty {
+            name: names.get_mut().add_fname(name),
+            ancestry: Ancestry {
+                ancestry: Vec::new(),
+            },
+            property_guid: None,
+            duplication_index: 0,
+            value,
+        })],
+    }))
+}
+
+pub fn set_float(
+    props: &mut Vec<Property>,
+    name: &str,
+    val: f32,
+    names: &mut SharedResource<NameMap>,
+) {
+    if let Some(Property::FloatProperty(float)) = find_mut(props, name) {
+        float.value = val.into();
+        return;
+    }
+    if val == 0.0 {
+        return;
+    }
+    props.push(Property::FloatProperty(FloatProperty {
+        name: names.get_mut().add_fname(name),
+        ancestry: Ancestry {
+            ancestry: Vec::new(),
+        },
+        property_guid: None,
+        duplication_index: 0,
+        value: val.into(),
+    }))
+}
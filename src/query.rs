@@ -0,0 +1,534 @@
+use super::*;
+use unreal_asset::{
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::{int_property::BytePropertyValue, Property, PropertyDataTrait},
+    types::PackageIndex,
+};
+
+// syntax:
+// word              name contains word
+// name:glob         name matches glob
+// class:glob        class matches glob
+// mesh:glob         any mesh path matches glob
+// prop:Path[op val] property exists or compares where op is one of = != > < >= <=
+// within:dist [of selected|name]
+// refs:selected|name
+// terms are and-ed by default and combine with and, or, not and brackets
+
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Name(String),
+    Glob(String),
+    Class(String),
+    Mesh(String),
+    Prop(Vec<String>, Option<(Op, String)>),
+    // in bevy space against the positions of the targets
+    Within(f32, Vec<Vec3>),
+    // exports of the targets and their components
+    Refs(std::collections::HashSet<usize>),
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+}
+
+enum Value {
+    Num(f64),
+    Text(String),
+}
+
+fn tokenise(query: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    for ch in query.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if quoted => token.push(ch),
+            '(' | ')' => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token))
+                }
+                tokens.push(ch.to_string())
+            }
+            ch if ch.is_whitespace() => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token))
+                }
+            }
+            ch => token.push(ch),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token)
+    }
+    tokens
+}
+
+/// case-insensitive match where * is any run of characters and ? is any one
+pub fn glob(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<_> = pattern.to_ascii_lowercase().chars().collect();
+    let text: Vec<_> = text.to_ascii_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // where to retry from when a star has to take more characters
+    let mut star = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&ch) if ch == '?' || ch == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    p = sp + 1;
+                    t = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|ch| ch == &'*')
+}
+
+pub struct Context<'a> {
+    pub map: &'a Asset,
+    pub actors: &'a [(Entity, &'a actor::Actor)],
+    pub selected: &'a [Entity],
+}
+
+impl Context<'_> {
+    /// the actors a target refers to
+    fn targets(&self, target: &str) -> Vec<&actor::Actor> {
+        self.actors
+            .iter()
+            .filter(|(entity, actor)| match target {
+                "selected" => self.selected.contains(entity),
+                name => actor.name.eq_ignore_ascii_case(name),
+            })
+            .map(|(_, actor)| *actor)
+            .collect()
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<String>,
+    pos: usize,
+    ctx: &'a Context<'a>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while matches!(self.peek(), Some("or" | "OR" | "|")) {
+            self.pos += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.not()?;
+        loop {
+            match self.peek() {
+                Some("and" | "AND" | "&") => self.pos += 1,
+                // juxtaposed terms are and-ed
+                Some(token) if !matches!(token, "or" | "OR" | "|" | ")") => (),
+                _ => break,
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some("not" | "NOT" | "!") => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.not()?)))
+            }
+            Some(token)
+                if token.len() > 1 && (token.starts_with('-') || token.starts_with('!')) =>
+            {
+                let token = self.next().unwrap_or_default();
+                Ok(Expr::Not(Box::new(self.term(&token[1..])?)))
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(open) if open == "(" => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(close) if close == ")" => Ok(expr),
+                    _ => Err("missing closing bracket".into()),
+                }
+            }
+            Some(close) if close == ")" => Err("unexpected closing bracket".into()),
+            Some(token) => self.term(&token),
+            None => Err("expected a term".into()),
+        }
+    }
+
+    fn term(&mut self, token: &str) -> Result<Expr, String> {
+        let Some((key, val)) = token.split_once(':') else {
+            return Ok(Expr::Name(token.to_ascii_lowercase()));
+        };
+        if val.is_empty() {
+            return Err(format!("{key}: needs a value"));
+        }
+        match key.to_ascii_lowercase().as_str() {
+            "name" => Ok(Expr::Glob(val.into())),
+            "class" => Ok(Expr::Class(val.into())),
+            "mesh" => Ok(Expr::Mesh(val.into())),
+            "prop" => {
+                // check two character operators first so >= isn't read as >
+                let ops = [
+                    (">=", Op::Ge),
+                    ("<=", Op::Le),
+                    ("!=", Op::Ne),
+                    ("=", Op::Eq),
+                    (">", Op::Gt),
+                    ("<", Op::Lt),
+                ];
+                let op = val
+                    .char_indices()
+                    .find_map(|(i, _)| {
+                        ops.iter()
+                            .find(|(sym, _)| val[i..].starts_with(sym))
+                            .map(|(sym, op)| (i, sym.len(), *op))
+                    })
+                    .map(|(i, len, op)| (&val[..i], Some((op, val[i + len..].to_string()))));
+                let (path, cmp) = op.unwrap_or((val, None));
+                if path.is_empty() {
+                    return Err("prop: needs a property name".into());
+                }
+                Ok(Expr::Prop(
+                    path.split('.').map(str::to_string).collect(),
+                    cmp,
+                ))
+            }
+            "within" => {
+                let dist: f32 = val.parse().map_err(|_| format!("{val} isn't a distance"))?;
+                let target = match self.peek() {
+                    Some("of") => {
+                        self.pos += 1;
+                        self.next().ok_or("within: of needs a target")?
+                    }
+                    _ => "selected".into(),
+                };
+                let targets = self.ctx.targets(&target);
+                if targets.is_empty() {
+                    return Err(format!("couldn't find {target}"));
+                }
+                Ok(Expr::Within(
+                    // unreal units are centimetres
+                    dist * 0.01,
+                    targets
+                        .into_iter()
+                        .map(|actor| actor.transform(self.ctx.map).translation)
+                        .collect(),
+                ))
+            }
+            "refs" => {
+                let targets = self.ctx.targets(val);
+                if targets.is_empty() {
+                    return Err(format!("couldn't find {val}"));
+                }
+                Ok(Expr::Refs(
+                    targets
+                        .into_iter()
+                        .flat_map(|actor| {
+                            let mut exports = actor.components(self.ctx.map);
+                            exports.push(actor.export);
+                            exports
+                        })
+                        .collect(),
+                ))
+            }
+            key => Err(format!("{key} isn't a known filter")),
+        }
+    }
+}
+
+/// parses a query into something which can be matched against actors
+pub fn parse(query: &str, ctx: &Context) -> Result<Matcher, String> {
+    let mut parser = Parser {
+        tokens: tokenise(query),
+        pos: 0,
+        ctx,
+    };
+    // there's nothing to narrow down by so everything matches
+    if parser.peek().is_none() {
+        return Ok(Matcher(Expr::Name(String::new())));
+    }
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(Matcher(expr)),
+        Some(token) => Err(format!("unexpected {token}")),
+    }
+}
+
+pub struct Matcher(Expr);
+
+impl Matcher {
+    pub fn matches(&self, map: &Asset, actor: &actor::Actor, registry: &Registry) -> bool {
+        eval(&self.0, map, actor, registry)
+    }
+}
+
+fn eval(expr: &Expr, map: &Asset, actor: &actor::Actor, registry: &Registry) -> bool {
+    match expr {
+        Expr::Or(a, b) => eval(a, map, actor, registry) || eval(b, map, actor, registry),
+        Expr::And(a, b) => eval(a, map, actor, registry) && eval(b, map, actor, registry),
+        Expr::Not(a) => !eval(a, map, actor, registry),
+        Expr::Name(name) => actor.name.to_ascii_lowercase().contains(name),
+        Expr::Glob(pattern) => glob(pattern, &actor.name),
+        Expr::Class(pattern) => glob(pattern, &actor.class),
+        Expr::Mesh(pattern) => blueprint::pieces(map, actor, registry)
+            .iter()
+            .any(|piece| glob(pattern, &piece.mesh)),
+        Expr::Prop(path, cmp) => {
+            let mut values = Vec::new();
+            for export in exports(map, actor) {
+                if let Some(norm) = map.asset_data.exports[export].get_normal_export() {
+                    for prop in norm.properties.iter() {
+                        if named(prop, &path[0]) {
+                            collect(map, prop, &path[1..], &mut values)
+                        }
+                    }
+                }
+            }
+            match cmp {
+                None => !values.is_empty(),
+                Some((op, val)) => values.iter().any(|value| compare(value, *op, val)),
+            }
+        }
+        Expr::Within(dist, targets) => {
+            let pos = actor.transform(map).translation;
+            targets.iter().any(|target| target.distance(pos) <= *dist)
+        }
+        Expr::Refs(targets) => {
+            let own = exports(map, actor);
+            let mut found = false;
+            for &export in own.iter() {
+                let Some(norm) = map.asset_data.exports[export].get_normal_export() else {
+                    continue;
+                };
+                for prop in norm.properties.iter() {
+                    props::visit(prop, &mut |prop| {
+                        if let Property::ObjectProperty(obj) = prop {
+                            found |= obj.value.index > 0
+                                && targets.contains(&(obj.value.index as usize - 1))
+                                && !own.contains(&(obj.value.index as usize - 1))
+                        }
+                    })
+                }
+            }
+            found
+        }
+    }
+}
+
+fn exports(map: &Asset, actor: &actor::Actor) -> Vec<usize> {
+    let mut exports = actor.components(map);
+    exports.push(actor.export);
+    exports
+}
+
+fn named(prop: &Property, name: &str) -> bool {
+    prop.get_name()
+        .get_content(|prop| prop.eq_ignore_ascii_case(name))
+}
+
+fn object(map: &Asset, index: PackageIndex) -> String {
+    match index.index {
+        0 => "null".into(),
+        i if i > 0 => map
+            .get_export(index)
+            .map(|ex| ex.get_base_export().object_name.get_owned_content())
+            .unwrap_or_default(),
+        _ => map
+            .get_import(index)
+            .map(|imp| imp.object_name.get_owned_content())
+            .unwrap_or_default(),
+    }
+}
+
+/// gathers the values at the end of a property path
+fn collect(map: &Asset, prop: &Property, path: &[String], values: &mut Vec<Value>) {
+    let axis = |axes: &[(&str, f64)]| {
+        let [axis] = path else { return None };
+        axes.iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(axis))
+            .map(|(_, val)| Value::Num(*val))
+    };
+    let value = match prop {
+        Property::ArrayProperty(arr) => {
+            for entry in arr.value.iter() {
+                collect(map, entry, path, values)
+            }
+            return;
+        }
+        Property::StructProperty(struc) => {
+            match path.split_first() {
+                Some((head, rest)) => {
+                    for field in struc.value.iter() {
+                        if named(field, head) {
+                            collect(map, field, rest, values)
+                        }
+                    }
+                }
+                None => (),
+            }
+            // vectors and colours are wrapped in a struct of the same name
+            if let [inner] = struc.value.as_slice() {
+                if inner.get_name() == struc.name {
+                    collect(map, inner, path, values)
+                }
+            }
+            return;
+        }
+        Property::VectorProperty(vec) => axis(&[
+            ("x", vec.value.x.0),
+            ("y", vec.value.y.0),
+            ("z", vec.value.z.0),
+        ]),
+        Property::RotatorProperty(rot) => axis(&[
+            ("pitch", rot.value.x.0),
+            ("yaw", rot.value.y.0),
+            ("roll", rot.value.z.0),
+        ]),
+        Property::ColorProperty(col) => axis(&[
+            ("r", col.color.r as f64),
+            ("g", col.color.g as f64),
+            ("b", col.color.b as f64),
+            ("a", col.color.a as f64),
+        ]),
+        Property::LinearColorProperty(col) => axis(&[
+            ("r", col.color.r.0 as f64),
+            ("g", col.color.g.0 as f64),
+            ("b", col.color.b.0 as f64),
+            ("a", col.color.a.0 as f64),
+        ]),
+        _ if !path.is_empty() => None,
+        Property::BoolProperty(bool) => Some(Value::Num(bool.value as u8 as f64)),
+        Property::FloatProperty(float) => Some(Value::Num(float.value.0 as f64)),
+        Property::DoubleProperty(double) => Some(Value::Num(double.value.0)),
+        Property::IntProperty(int) => Some(Value::Num(int.value as f64)),
+        Property::Int8Property(int) => Some(Value::Num(int.value as f64)),
+        Property::Int16Property(int) => Some(Value::Num(int.value as f64)),
+        Property::Int64Property(int) => Some(Value::Num(int.value as f64)),
+        Property::UInt16Property(int) => Some(Value::Num(int.value as f64)),
+        Property::UInt32Property(int) => Some(Value::Num(int.value as f64)),
+        Property::UInt64Property(int) => Some(Value::Num(int.value as f64)),
+        Property::ByteProperty(byte) => match &byte.value {
+            BytePropertyValue::Byte(byte) => Some(Value::Num(*byte as f64)),
+            BytePropertyValue::FName(name) => Some(Value::Text(name.get_owned_content())),
+        },
+        Property::EnumProperty(enm) => enm
+            .value
+            .as_ref()
+            .map(|val| Value::Text(val.get_owned_content())),
+        Property::NameProperty(name) => Some(Value::Text(name.value.get_owned_content())),
+        Property::StrProperty(str) => str.value.clone().map(Value::Text),
+        Property::ObjectProperty(obj) => Some(Value::Text(object(map, obj.value))),
+        _ => None,
+    };
+    values.extend(value)
+}
+
+fn compare(value: &Value, op: Op, val: &str) -> bool {
+    let num = match val.to_ascii_lowercase().as_str() {
+        "true" => Some(1.0),
+        "false" => Some(0.0),
+        val => val.parse::<f64>().ok(),
+    };
+    match (value, num) {
+        (Value::Num(a), Some(b)) => match op {
+            Op::Eq => a == &b,
+            Op::Ne => a != &b,
+            Op::Gt => a > &b,
+            Op::Lt => a < &b,
+            Op::Ge => a >= &b,
+            Op::Le => a <= &b,
+        },
+        (Value::Num(_), None) => matches!(op, Op::Ne),
+        (Value::Text(text), _) => match op {
+            // enums are serialised with their type prefixed
+            Op::Eq => glob(val, text) || glob(&format!("*::{val}"), text),
+            Op::Ne => !(glob(val, text) || glob(&format!("*::{val}"), text)),
+            Op::Gt => text.to_ascii_lowercase() > val.to_ascii_lowercase(),
+            Op::Lt => text.to_ascii_lowercase() < val.to_ascii_lowercase(),
+            Op::Ge => text.to_ascii_lowercase() >= val.to_ascii_lowercase(),
+            Op::Le => text.to_ascii_lowercase() <= val.to_ascii_lowercase(),
+        },
+    }
+}
+
+#[test]
+fn globbing() {
+    assert!(glob("/Game/Props/*", "/Game/Props/Crates/SM_Crate"));
+    assert!(glob("pointlight", "PointLight"));
+    assert!(glob("*Light", "SpotLight"));
+    assert!(glob("SM_?rate*", "SM_Crate_02"));
+    assert!(!glob("*Light", "LightmassImportanceVolume"));
+    assert!(!glob("/Game/Props/*", "/Game/Maps/Castle"));
+}
+
+#[test]
+fn tokens() {
+    assert_eq!(
+        tokenise(r#"class:PointLight and (prop:"Light Color.R">200 or -name:Torch*)"#),
+        [
+            "class:PointLight",
+            "and",
+            "(",
+            "prop:Light Color.R>200",
+            "or",
+            "-name:Torch*",
+            ")"
+        ]
+    );
+}
+
+#[test]
+fn blank() {
+    let mut map = regression::sample();
+    let actor = regression::stand_ins(&mut map).remove(0);
+    let ctx = Context {
+        map: &map,
+        actors: &[],
+        selected: &[],
+    };
+    for query in ["", "   "] {
+        assert!(parse(query, &ctx)
+            .unwrap()
+            .matches(&map, &actor, &Registry::default()));
+    }
+    assert!(parse("()", &ctx).is_err());
+}
@@ -0,0 +1,328 @@
+use super::*;
+use unreal_asset::{
+    cast,
+    engine_version::EngineVersion,
+    exports::{Export, ExportBaseTrait, ExportNormalTrait},
+    properties::PropertyDataTrait,
+    reader::archive_trait::ArchiveTrait,
+    types::{PackageIndex, PackageIndexTrait},
+};
+
+/// a reference from one object to another and where it's stored
+pub struct Ref {
+    pub from: PackageIndex,
+    pub to: PackageIndex,
+    pub via: String,
+}
+
+#[derive(Default, Resource)]
+pub struct Graph {
+    pub open: bool,
+    target: Option<PackageIndex>,
+    history: Vec<PackageIndex>,
+    // every reference in the map which is gathered on refresh
+    refs: Vec<Ref>,
+    // export and import counts when gathered so adding or removing actors refreshes
+    size: (usize, usize),
+    stale: bool,
+}
+
+impl Graph {
+    /// shows the references of an object
+    pub fn focus(&mut self, target: PackageIndex) {
+        if let Some(old) = self.target.replace(target) {
+            if old != target {
+                self.history.push(old)
+            }
+        }
+        self.open = true;
+    }
+}
+
+/// gathers every reference an export makes given the level's actors
+pub fn outgoing(map: &Asset, export: usize, actors: &[PackageIndex]) -> Vec<Ref> {
+    let from = PackageIndex::new(export as i32 + 1);
+    let mut refs = Vec::new();
+    let mut push = |via: &str, to: PackageIndex| {
+        if to.index != 0 {
+            refs.push(Ref {
+                from,
+                to,
+                via: via.into(),
+            })
+        }
+    };
+    // the ref functions need mutable access so work on a copy
+    let mut ex = map.asset_data.exports[export].clone();
+    if let Some(level) = cast!(Export, LevelExport, &ex) {
+        for actor in level.actors.iter() {
+            push("actors", *actor)
+        }
+    }
+    // 5.1+ components list the imports they use in their extras
+    let base = ex.get_base_export();
+    if map.get_engine_version() >= EngineVersion::VER_UE5_1
+        && actors.contains(&base.outer_index)
+        && base.object_name != "Model"
+    {
+        actor::on_extra_refs(&mut ex, &mut |index, _| push("extras", *index));
+    }
+    if let Some(norm) = ex.get_normal_export_mut() {
+        for prop in norm.properties.iter_mut() {
+            let name = prop.get_name().get_owned_content();
+            actor::on_prop_refs(prop, &mut |index| push(&name, *index));
+        }
+    }
+    let base = ex.get_base_export();
+    push("class", base.class_index);
+    push("template", base.template_index);
+    push("super", base.super_index);
+    push("outer", base.outer_index);
+    for (via, deps) in [
+        (
+            "serialization before serialization",
+            &base.serialization_before_serialization_dependencies,
+        ),
+        (
+            "create before serialization",
+            &base.create_before_serialization_dependencies,
+        ),
+        (
+            "serialization before create",
+            &base.serialization_before_create_dependencies,
+        ),
+        (
+            "create before create",
+            &base.create_before_create_dependencies,
+        ),
+    ] {
+        for dep in deps {
+            push(via, *dep)
+        }
+    }
+    refs
+}
+
+/// gathers every reference in the map
+fn gather(map: &Asset) -> Vec<Ref> {
+    let actors: Vec<_> = map
+        .asset_data
+        .exports
+        .iter()
+        .find_map(|ex| cast!(Export, LevelExport, ex))
+        .map(|level| level.actors.clone())
+        .unwrap_or_default();
+    let mut refs: Vec<_> = (0..map.asset_data.exports.len())
+        .flat_map(|export| outgoing(map, export, &actors))
+        .collect();
+    refs.extend(map.imports.iter().enumerate().filter_map(|(i, import)| {
+        (import.outer_index.index != 0).then(|| Ref {
+            from: PackageIndex::new(-(i as i32) - 1),
+            to: import.outer_index,
+            via: "outer".into(),
+        })
+    }));
+    refs
+}
+
+#[test]
+fn covered() {
+    let map = crate::regression::sample();
+    for (i, ex) in map.asset_data.exports.iter().enumerate() {
+        let refs = outgoing(&map, i, &[]);
+        let mut ex = ex.clone();
+        let mut check = |index: &mut PackageIndex| {
+            assert!(
+                index.index == 0 || refs.iter().any(|r| r.to == *index),
+                "{} missing from {i}",
+                index.index
+            )
+        };
+        actor::on_export_refs(&mut ex, &mut check);
+        actor::on_import_refs(&mut ex, &mut check);
+    }
+}
+
+/// names an object with its index
+pub fn name(map: &Asset, index: PackageIndex) -> String {
+    match index.index {
+        0 => "null".into(),
+        i if i > 0 => map
+            .get_export(index)
+            .map(|ex| ex.get_base_export().object_name.get_owned_content())
+            .map(|name| format!("{name} ({i})")),
+        i => map
+            .get_import(index)
+            .map(|imp| format!("{} ({i})", imp.object_name.get_owned_content())),
+    }
+    .unwrap_or_else(|| format!("invalid ({})", index.index))
+}
+
+/// the actor export an object belongs to
+pub fn owner(
+    map: &Asset,
+    index: PackageIndex,
+    actors: &[(Entity, &actor::Actor)],
+) -> Option<Entity> {
+    let mut index = index;
+    // outers can't really loop but guard against broken maps
+    for _ in 0..64 {
+        if !index.is_export() {
+            return None;
+        }
+        if let Some((entity, _)) = actors
+            .iter()
+            .find(|(_, actor)| actor.export as i32 + 1 == index.index)
+        {
+            return Some(*entity);
+        }
+        index = map.get_export(index)?.get_base_export().outer_index;
+    }
+    None
+}
+
+pub fn toggle(
+    _: Trigger<triggers::References>,
+    mut graph: ResMut<Graph>,
+    selected: Query<&actor::Actor, With<actor::Selected>>,
+) {
+    match selected.get_single() {
+        Ok(actor) if !graph.open => graph.focus(PackageIndex::new(actor.export as i32 + 1)),
+        _ => graph.open = !graph.open,
+    }
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut graph: ResMut<Graph>,
+    map: NonSend<Map>,
+    consts: Res<Constants>,
+    actors: Query<(Entity, &actor::Actor)>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+) {
+    let Some((map, ..)) = &map.0 else {
+        graph.open = false;
+        return;
+    };
+    if !graph.open {
+        return;
+    }
+    let size = (map.asset_data.exports.len(), map.imports.len());
+    if graph.stale || graph.size != size {
+        graph.refs = gather(map);
+        graph.size = size;
+        graph.stale = false;
+    }
+    let actors: Vec<_> = actors.iter().collect();
+    let mut open = true;
+    let mut navigate = None;
+    let mut select = None;
+    egui::Window::new("references")
+        .open(&mut open)
+        .default_width(350.0)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(!graph.history.is_empty(), egui::Button::new("⏴"))
+                    .on_hover_text("back")
+                    .clicked()
+                {
+                    if let Some(back) = graph.history.pop() {
+                        graph.target = Some(back)
+                    }
+                }
+                if ui.button("⟳").on_hover_text("refresh").clicked() {
+                    graph.stale = true
+                }
+                let mut index = graph.target.map(|i| i.index).unwrap_or_default();
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut index).range(
+                            -(map.imports.len() as i32)..=map.asset_data.exports.len() as i32,
+                        ),
+                    )
+                    .on_hover_text("negative for imports")
+                    .changed()
+                {
+                    navigate = Some(PackageIndex::new(index))
+                }
+            });
+            let Some(target) = graph.target else {
+                ui.label("select an actor and press r or enter an index");
+                return;
+            };
+            ui.horizontal(|ui| {
+                ui.heading(name(map, target));
+                if let Some(entity) = owner(map, target, &actors) {
+                    if ui.button("select").clicked() {
+                        select = Some(entity)
+                    }
+                }
+            });
+            if let Some(import) = map.get_import(target) {
+                ui.label(format!(
+                    "{}.{}",
+                    import.class_package.get_owned_content(),
+                    import.class_name.get_owned_content()
+                ));
+            }
+            let mut list = |ui: &mut egui::Ui, heading: &str, refs: Vec<(&str, PackageIndex)>| {
+                egui::CollapsingHeader::new(format!("{heading} ({})", refs.len()))
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .id_source(heading)
+                            .max_height(250.0)
+                            .auto_shrink([false, true])
+                            .show_rows(
+                                ui,
+                                ui.text_style_height(&egui::TextStyle::Body),
+                                refs.len(),
+                                |ui, range| {
+                                    for (via, index) in &refs[range] {
+                                        ui.horizontal(|ui| {
+                                            if ui.link(name(map, *index)).clicked() {
+                                                navigate = Some(*index)
+                                            }
+                                            ui.weak(*via);
+                                        });
+                                    }
+                                },
+                            );
+                    });
+            };
+            list(
+                ui,
+                "outgoing",
+                graph
+                    .refs
+                    .iter()
+                    .filter(|r| r.from == target)
+                    .map(|r| (r.via.as_str(), r.to))
+                    .collect(),
+            );
+            list(
+                ui,
+                "incoming",
+                graph
+                    .refs
+                    .iter()
+                    .filter(|r| r.to == target)
+                    .map(|r| (r.via.as_str(), r.from))
+                    .collect(),
+            );
+        });
+    if let Some(index) = navigate {
+        graph.focus(index)
+    }
+    if let Some(entity) = select {
+        for old in selected.iter() {
+            outliner::deselect(&mut commands, &mut cubes, &consts, old);
+        }
+        outliner::select(&mut commands, &mut cubes, &consts, entity);
+        commands.trigger(triggers::Focus);
+    }
+    graph.open &= open;
+}
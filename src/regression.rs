@@ -0,0 +1,246 @@
+//! open, edit and save maps from several engine versions to catch serialisation regressions
+//!
+//! the maps aren't checked in so put small ones in src/regression/<version>/
+//! e.g. src/regression/5.1/Stage.umap with its Stage.uexp beside it
+//! then run them with cargo test -- --ignored
+use super::*;
+use std::io::Cursor;
+use unreal_asset::{
+    engine_version::EngineVersion::{self, *},
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::{object_property::ObjectProperty, Property, PropertyDataTrait},
+    reader::archive_trait::ArchiveTrait,
+    types::{PackageIndex, PackageIndexTrait},
+    unversioned::ancestry::Ancestry,
+};
+
+const VERSIONS: [(&str, EngineVersion); 4] = [
+    ("4.25", VER_UE4_25),
+    ("4.27", VER_UE4_27),
+    ("5.1", VER_UE5_1),
+    ("5.2", VER_UE5_2),
+];
+
+/// gets every map in the fixture folders with its version
+fn fixtures() -> Vec<(std::path::PathBuf, EngineVersion)> {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src/regression");
+    let maps: Vec<_> = VERSIONS
+        .into_iter()
+        .flat_map(|(folder, version)| {
+            walkdir::WalkDir::new(root.join(folder))
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "umap"))
+                .map(move |entry| (entry.into_path(), version))
+        })
+        .collect();
+    // passing without checking anything would be misleading
+    assert!(
+        !maps.is_empty(),
+        "no maps in {} so nothing can be checked",
+        root.display()
+    );
+    maps
+}
+
+/// saves to memory the same way asset::save does
+pub fn write(map: &mut Asset) -> (Vec<u8>, Option<Vec<u8>>) {
+    map.rebuild_name_map();
+    let mut asset = Cursor::new(Vec::new());
+    let mut bulk = map
+        .asset_data
+        .use_event_driven_loader
+        .then(|| Cursor::new(Vec::new()));
+    map.write_data(&mut asset, bulk.as_mut()).unwrap();
+    (asset.into_inner(), bulk.map(Cursor::into_inner))
+}
+
+pub fn read((asset, bulk): (Vec<u8>, Option<Vec<u8>>), version: EngineVersion) -> Asset {
+    unreal_asset::Asset::new(
+        Wrapper::Bytes(Cursor::new(asset)),
+        bulk.map(|bulk| Wrapper::Bytes(Cursor::new(bulk))),
+        version,
+        None,
+    )
+    .unwrap()
+}
+
+/// the package from the mesh tests which does for edits that don't need a level
+pub fn sample() -> Asset {
+    read(
+        (
+            include_bytes!("extras/tests/SM_Cybercity_Hook_End.uasset").to_vec(),
+            Some(include_bytes!("extras/tests/SM_Cybercity_Hook_End.uexp").to_vec()),
+        ),
+        VER_UE4_27,
+    )
+}
+
+/// gives the sample's exports without components a location so they read as actors
+pub fn stand_ins(map: &mut Asset) -> Vec<actor::Actor> {
+    let loose: Vec<_> = (0..map.asset_data.exports.len())
+        .filter(|&i| {
+            let ex = &map.asset_data.exports[i];
+            ex.get_normal_export().is_some()
+                && !ex
+                    .get_base_export()
+                    .create_before_serialization_dependencies
+                    .iter()
+                    .any(|dep| dep.is_export())
+        })
+        .collect();
+    assert!(!loose.is_empty(), "the sample has nothing to stand in");
+    let mut names = map.get_name_map();
+    loose
+        .into_iter()
+        .enumerate()
+        .map(|(i, export)| {
+            let norm = map.asset_data.exports[export]
+                .get_normal_export_mut()
+                .unwrap();
+            let location = bevy::math::DVec3::splat(100.0 * (i + 1) as f64);
+            props::set_vector(&mut norm.properties, actor::LOCATION, location, &mut names);
+            actor::Actor::new(map, PackageIndex::new(export as i32 + 1))
+                .unwrap()
+                .1
+        })
+        .collect()
+}
+
+/// adds a reference from one export to another like an attachment would
+pub fn point(map: &mut Asset, from: usize, to: usize) {
+    let mut names = map.get_name_map();
+    let norm = map.asset_data.exports[from]
+        .get_normal_export_mut()
+        .unwrap();
+    norm.properties
+        .push(Property::ObjectProperty(ObjectProperty {
+            name: names.get_mut().add_fname("Target"),
+            ancestry: Ancestry {
+                ancestry: Vec::new(),
+            },
+            property_guid: None,
+            duplication_index: 0,
+            value: PackageIndex::new(to as i32 + 1),
+        }))
+}
+
+/// where the reference added by point goes
+pub fn target(map: &Asset, from: usize) -> Option<PackageIndex> {
+    map.asset_data.exports[from]
+        .get_normal_export()?
+        .properties
+        .iter()
+        .find_map(|prop| match prop {
+            Property::ObjectProperty(obj) if prop.get_name() == "Target" => Some(obj.value),
+            _ => None,
+        })
+}
+
+fn actors(map: &Asset) -> Vec<actor::Actor> {
+    actor::get_actors(map)
+        .into_iter()
+        .filter_map(|index| actor::Actor::new(map, index).ok())
+        .map(|(_, actor)| actor)
+        .collect()
+}
+
+fn transforms(map: &Asset) -> Vec<Transform> {
+    actors(map)
+        .iter()
+        .map(|actor| actor.transform(map))
+        .collect()
+}
+
+fn assert_same(path: &std::path::Path, a: &[Transform], b: &[Transform]) {
+    assert_eq!(a.len(), b.len(), "{}", path.display());
+    for (a, b) in a.iter().zip(b) {
+        assert!(
+            a.translation.abs_diff_eq(b.translation, 1e-3)
+                && a.rotation.abs_diff_eq(b.rotation, 1e-3)
+                && a.scale.abs_diff_eq(b.scale, 1e-3),
+            "{}: {a:?} != {b:?}",
+            path.display()
+        )
+    }
+}
+
+#[test]
+#[ignore = "needs maps in src/regression"]
+fn round_trip() {
+    for (path, version) in fixtures() {
+        let mut map = asset::open(&path, version).unwrap();
+        let (asset, bulk) = write(&mut map);
+        assert!(
+            asset == std::fs::read(&path).unwrap(),
+            "{} changed",
+            path.display()
+        );
+        if let Some(bulk) = bulk {
+            assert!(
+                bulk == std::fs::read(path.with_extension("uexp")).unwrap(),
+                "{} bulk changed",
+                path.display()
+            );
+        }
+    }
+}
+
+#[test]
+#[ignore = "needs maps in src/regression"]
+fn duplicate() {
+    for (path, version) in fixtures() {
+        let mut map = asset::open(&path, version).unwrap();
+        let before = transforms(&map);
+        for actor in actors(&map) {
+            actor.duplicate(&mut map, &mut Vec::new());
+        }
+        let map = read(write(&mut map), version);
+        // duplicates are added to the end of the level in order
+        let after = transforms(&map);
+        assert_same(&path, &before, &after[..after.len().min(before.len())]);
+        assert_same(&path, &before, &after[before.len().min(after.len())..]);
+    }
+}
+
+#[test]
+#[ignore = "needs maps in src/regression"]
+fn transplant() {
+    for (path, version) in fixtures() {
+        let donor = asset::open(&path, version).unwrap();
+        let mut map = asset::open(&path, version).unwrap();
+        let before = transforms(&map);
+        // 5.1 onwards also goes through the import references in extras
+        for actor in actors(&donor) {
+            actor
+                .transplant(&mut map, &donor, &mut Vec::new(), &mut Vec::new())
+                .unwrap();
+        }
+        let map = read(write(&mut map), version);
+        let after = transforms(&map);
+        assert_same(&path, &before, &after[..after.len().min(before.len())]);
+        assert_same(&path, &before, &after[before.len().min(after.len())..]);
+    }
+}
+
+#[test]
+#[ignore = "needs maps in src/regression"]
+fn delete() {
+    for (path, version) in fixtures() {
+        let mut map = asset::open(&path, version).unwrap();
+        let before = actors(&map);
+        // children would be moved to stay where they were so leave them be
+        let Some(i) = before
+            .iter()
+            .position(|actor| actor::attached(&map, &[actor]).is_empty())
+        else {
+            continue;
+        };
+        let mut expected = transforms(&map);
+        expected.remove(i);
+        actor::unlink(&mut map, &[&before[i]]);
+        before[i].delete(&mut map);
+        let map = read(write(&mut map), version);
+        assert_same(&path, &expected, &transforms(&map));
+    }
+}
@@ -0,0 +1,354 @@
+use super::*;
+use std::path::PathBuf;
+use unreal_asset::types::PackageIndex;
+
+// swaps each selected actor for a copy of a template which takes over its transform
+
+pub enum Template {
+    // an actor in the shown map
+    Actor(Entity),
+    // an actor in a donor map read as one of the versions
+    Donor {
+        from: PathBuf,
+        version: usize,
+        export: usize,
+    },
+    Prefab(PathBuf),
+}
+
+#[derive(Default, Resource)]
+pub struct Replace {
+    pub open: bool,
+    // with its name for the window
+    template: Option<(Template, String)>,
+    // copy properties with matching names from the originals
+    carry: bool,
+}
+
+pub fn toggle(_: Trigger<triggers::ReplaceTool>, mut replace: ResMut<Replace>) {
+    replace.open = !replace.open
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut replace: ResMut<Replace>,
+    transplant: NonSend<Transplant>,
+    selected: Query<(Entity, &actor::Actor), With<actor::Selected>>,
+) {
+    if !replace.open {
+        return;
+    }
+    let mut open = true;
+    let replace = replace.as_mut();
+    egui::Window::new("replace with")
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx.ctx_mut(), |ui| {
+            match &replace.template {
+                Some((_, name)) => ui.label(format!("template: {name}")),
+                None => ui.weak("pick a template"),
+            };
+            ui.horizontal(|ui| {
+                let single = selected.get_single().ok();
+                if ui
+                    .add_enabled(single.is_some(), egui::Button::new("selected"))
+                    .on_hover_text("use the one selected actor")
+                    .clicked()
+                {
+                    if let Some((entity, actor)) = single {
+                        replace.template = Some((Template::Actor(entity), actor.name.clone()))
+                    }
+                }
+                let picked = match &transplant.0 {
+                    Some((_, from, others, picked, version)) if picked.len() == 1 => {
+                        Some((from, &others[picked[0]], *version))
+                    }
+                    _ => None,
+                };
+                if ui
+                    .add_enabled(picked.is_some(), egui::Button::new("transplant pick"))
+                    .on_hover_text("use the one actor picked in the transplant window")
+                    .clicked()
+                {
+                    if let Some((from, actor, version)) = picked {
+                        replace.template = Some((
+                            Template::Donor {
+                                from: from.clone(),
+                                version,
+                                export: actor.export,
+                            },
+                            actor.name.clone(),
+                        ))
+                    }
+                }
+                ui.menu_button("prefab", |ui| {
+                    let prefabs = prefab::saved();
+                    if prefabs.is_empty() {
+                        ui.label("no prefabs saved yet");
+                    }
+                    for path in prefabs {
+                        let name = prefab::name(&path);
+                        if ui.button(&name).clicked() {
+                            replace.template = Some((Template::Prefab(path), name));
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+            ui.checkbox(&mut replace.carry, "carry over matching properties");
+            ui.add_enabled_ui(replace.template.is_some() && !selected.is_empty(), |ui| {
+                if ui
+                    .button("replace selected")
+                    .on_hover_text("the originals are deleted with what pointed at them moved over")
+                    .clicked()
+                {
+                    commands.trigger(triggers::ReplaceSelected)
+                }
+            });
+        });
+    replace.open &= open;
+}
+
+/// moves copies so the first of them takes the place of an original
+fn fit(
+    commands: &mut Commands,
+    map: &mut Asset,
+    copy: &[(Vec<actor::Piece>, actor::Actor)],
+    target: Transform,
+) {
+    let Some((_, first)) = copy.iter().find(|(_, actor)| actor.parent(map).is_none()) else {
+        return;
+    };
+    let reference = first.transform(map);
+    // a zero scale can't be divided out
+    let scale = match reference.scale.cmpeq(Vec3::ZERO).any() {
+        true => Vec3::ONE,
+        false => target.scale / reference.scale,
+    };
+    action::arrange(
+        commands,
+        map,
+        copy,
+        reference.translation,
+        Transform {
+            translation: target.translation - reference.translation,
+            rotation: target.rotation * reference.rotation.inverse(),
+            scale,
+        },
+    )
+}
+
+pub fn replace(
+    _: Trigger<triggers::ReplaceSelected>,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    replace: Res<Replace>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    mut map: NonSendMut<Map>,
+    actors: Query<&actor::Actor>,
+    selected: Query<(Entity, &actor::Actor), With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+    recorder: Res<patch::Recorder>,
+) {
+    let Some((map, _, export_names, import_names)) = &mut map.0 else {
+        return;
+    };
+    let Some((template, name)) = &replace.template else {
+        return;
+    };
+    let mut fail = |message: String| {
+        notif.send(Notif {
+            message,
+            kind: Error,
+        });
+    };
+    // donors are only read once for every actor replaced
+    let donor = match template {
+        Template::Actor(_) => None,
+        Template::Donor { from, version, .. } => match asset::open(from, VERSIONS[*version].0) {
+            Ok(donor) => Some(donor),
+            Err(e) => return fail(e.to_string()),
+        },
+        Template::Prefab(path) => match std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| clipboard::decode(&text))
+        {
+            Ok(donor) => Some(donor),
+            Err(e) => return fail(e),
+        },
+    };
+    let local = match template {
+        Template::Actor(entity) => match actors.get(*entity) {
+            Ok(actor) => Some(actor),
+            Err(_) => return fail(format!("{name} isn't in this map anymore")),
+        },
+        _ => None,
+    };
+    let donated = match (template, &donor) {
+        (Template::Donor { export, .. }, Some(donor)) => {
+            match actor::Actor::new(donor, PackageIndex::new(*export as i32 + 1)) {
+                Ok((_, actor)) => Some(actor),
+                Err(e) => return fail(e.to_string()),
+            }
+        }
+        _ => None,
+    };
+    // the template can't replace itself
+    let targets: Vec<_> = selected
+        .iter()
+        .filter(|(entity, _)| !matches!(template, Template::Actor(own) if own == entity))
+        .collect();
+    if targets.is_empty() {
+        notif.send(Notif {
+            message: "no actors to replace".into(),
+            kind: Warning,
+        });
+        return;
+    }
+    for (entity, _) in selected.iter() {
+        outliner::deselect(&mut commands, &mut cubes, &consts, entity);
+    }
+    let mut batch = Vec::new();
+    let mut replaced = Vec::with_capacity(targets.len());
+    let mut carried = 0;
+    for (entity, original) in targets {
+        let copy = match (local, &donated, &donor) {
+            (Some(local), ..) => {
+                action::stamp(&mut commands, &mut notif, map, export_names, &[local])
+            }
+            (_, Some(donated), Some(donor)) => {
+                let len = match actor::transplant_group(
+                    &[donated],
+                    map,
+                    donor,
+                    export_names,
+                    import_names,
+                )
+                .remove(0)
+                {
+                    Ok(len) => len,
+                    // it'd be refused the same way for every actor
+                    Err(problems) => {
+                        notif.send(Notif {
+                            message: format!(
+                                "couldn't transplant {name}:\n{}",
+                                problems.join("\n")
+                            ),
+                            kind: Error,
+                        });
+                        break;
+                    }
+                };
+                if let Template::Donor { from, version, .. } = template {
+                    commands.trigger(triggers::Record(patch::Op::Transplant {
+                        from: from.clone(),
+                        version: Some(VERSIONS[*version].1.into()),
+                        actor: patch::name(donor, donated.export),
+                        name: patch::name(map, len),
+                    }));
+                }
+                match actor::Actor::new(map, PackageIndex::new(len as i32 + 1)) {
+                    Ok((pieces, new)) => {
+                        export_names[len] = new.name.clone();
+                        vec![(pieces, new)]
+                    }
+                    Err(e) => {
+                        notif.send(Notif {
+                            message: format!("couldn't place {}: {e}", donated.name),
+                            kind: Error,
+                        });
+                        break;
+                    }
+                }
+            }
+            (_, None, Some(donor)) => {
+                let from = match template {
+                    Template::Prefab(path) => Some(path.as_path()),
+                    _ => None,
+                };
+                let (copy, problems) = clipboard::place(
+                    &mut commands,
+                    map,
+                    export_names,
+                    import_names,
+                    donor,
+                    from,
+                    Vec3::ZERO,
+                );
+                for problem in problems {
+                    notif.send(Notif {
+                        message: format!("couldn't place {problem}"),
+                        kind: Error,
+                    });
+                }
+                copy
+            }
+            _ => break,
+        };
+        let Some((_, replacement)) = copy.first() else {
+            continue;
+        };
+        fit(&mut commands, map, &copy, original.transform(map));
+        if replace.carry {
+            let before = recorder
+                .recording
+                .then(|| patch::snapshot(map, replacement));
+            carried += original.carry_over(map, replacement);
+            if let Some(before) = before {
+                let (ops, added) = patch::changes(map, replacement, &before);
+                for op in ops {
+                    commands.trigger(triggers::Record(op));
+                }
+                if !added.is_empty() {
+                    notif.send(Notif {
+                        message: format!(
+                            "{} gained {} properties which can't be recorded in the patch",
+                            replacement.name,
+                            added.len()
+                        ),
+                        kind: Warning,
+                    });
+                }
+            }
+        }
+        // anything pointing at or attached to the original goes to the replacement instead
+        actor::retarget(map, original, replacement);
+        commands.trigger(triggers::Record(patch::Op::Retarget {
+            actor: patch::name(map, original.export),
+            to: patch::name(map, replacement.export),
+        }));
+        replaced.push((entity, original));
+        batch.extend(copy);
+    }
+    let count = replaced.len();
+    deletion::remove(&mut commands, &mut notif, map, &replaced);
+    dialog::spawn_new(
+        map,
+        batch,
+        &mut commands,
+        &mut registry,
+        &content,
+        &appdata,
+        &consts,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+    notif.send(Notif {
+        message: match replace.carry {
+            true => {
+                format!("replaced {count} actors with {name} carrying over {carried} properties")
+            }
+            false => format!("replaced {count} actors with {name}"),
+        },
+        kind: Success,
+    });
+}
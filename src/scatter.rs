@@ -0,0 +1,243 @@
+use super::*;
+
+// copies of the actors picked when the brush was started are dropped
+// onto whatever's under random points around each click
+
+#[derive(Resource)]
+pub struct Scatter {
+    pub open: bool,
+    // the actors being scattered while the brush is active
+    source: Vec<Entity>,
+    radius: f32,
+    count: usize,
+    // ranges to pick from with yaw in degrees
+    yaw: [f32; 2],
+    scale: [f32; 2],
+    seed: u64,
+}
+
+impl Default for Scatter {
+    fn default() -> Self {
+        Self {
+            open: false,
+            source: Vec::new(),
+            radius: 5.0,
+            count: 5,
+            yaw: [0.0, 360.0],
+            scale: [0.8, 1.2],
+            // only needs to be different each run
+            seed: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(1, |time| time.as_nanos() as u64)
+                | 1,
+        }
+    }
+}
+
+impl Scatter {
+    pub fn brushing(&self) -> bool {
+        !self.source.is_empty()
+    }
+
+    /// a xorshift number between 0 and 1 which is plenty for placing rocks
+    fn random(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        (self.seed >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn between(&mut self, [min, max]: [f32; 2]) -> f32 {
+        min + (max - min) * self.random()
+    }
+}
+
+pub fn toggle(_: Trigger<triggers::ScatterTool>, mut scatter: ResMut<Scatter>) {
+    scatter.open = !scatter.open
+}
+
+fn range(ui: &mut egui::Ui, label: &str, range: &mut [f32; 2], speed: f32) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let [min, max] = range;
+        ui.add(egui::DragValue::new(min).speed(speed).prefix("min: "));
+        ui.add(egui::DragValue::new(max).speed(speed).prefix("max: "));
+    });
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut notif: EventWriter<Notif>,
+    mut scatter: ResMut<Scatter>,
+    selected: Query<Entity, With<actor::Selected>>,
+) {
+    if !scatter.open {
+        scatter.source.clear();
+        return;
+    }
+    let mut open = true;
+    let mut start = false;
+    let scatter = scatter.as_mut();
+    egui::Window::new("scatter")
+        .open(&mut open)
+        .resizable(false)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.add(egui::Slider::new(&mut scatter.radius, 0.5..=50.0).text("radius"));
+            ui.add(egui::Slider::new(&mut scatter.count, 1..=50).text("per click"));
+            range(ui, "yaw", &mut scatter.yaw, 1.0);
+            range(ui, "scale", &mut scatter.scale, 0.01);
+            match scatter.brushing() {
+                true => {
+                    ui.label(format!(
+                        "click in the viewport to scatter {} actors",
+                        scatter.source.len()
+                    ));
+                    if ui.button("stop").on_hover_text("or press escape").clicked() {
+                        scatter.source.clear()
+                    }
+                }
+                false => {
+                    if ui
+                        .button("start")
+                        .on_hover_text("scatter copies of the selection")
+                        .clicked()
+                    {
+                        start = true
+                    }
+                }
+            }
+        });
+    if start {
+        scatter.source = selected.iter().collect();
+        if scatter.source.is_empty() {
+            notif.send(Notif {
+                message: "select the actors to scatter first".into(),
+                kind: Warning,
+            });
+        }
+    }
+    scatter.open &= open;
+}
+
+/// picks where copies go around a click in the viewport
+pub fn brush(
+    mut commands: Commands,
+    mut scatter: ResMut<Scatter>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    camera: Query<&bevy_mod_raycast::deferred::RaycastSource<()>>,
+    mut raycast: bevy_mod_raycast::immediate::Raycast,
+    mut ctx: bevy_egui::EguiContexts,
+) {
+    if !scatter.brushing() {
+        return;
+    }
+    if keys.just_released(KeyCode::Escape) {
+        scatter.source.clear();
+        return;
+    }
+    if ctx.ctx_mut().is_pointer_over_area() || !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some((_, hit)) = camera.single().get_nearest_intersection() else {
+        return;
+    };
+    let scatter = scatter.as_mut();
+    let normal = hit.normal().try_normalize().unwrap_or(Vec3::Y);
+    let (across, along) = normal.any_orthonormal_pair();
+    let mut spots = Vec::with_capacity(scatter.count);
+    for _ in 0..scatter.count {
+        // the square root spreads points evenly over the disc
+        let distance = scatter.radius * scatter.random().sqrt();
+        let angle = std::f32::consts::TAU * scatter.random();
+        let origin = hit.position()
+            + (across * angle.cos() + along * angle.sin()) * distance
+            + normal * scatter.radius;
+        let ray = Ray3d::new(origin, -normal);
+        let Some((_, below)) = raycast
+            .cast_ray(
+                ray,
+                &bevy_mod_raycast::immediate::RaycastSettings::default(),
+            )
+            .first()
+        else {
+            continue;
+        };
+        let (yaw, scale) = (scatter.yaw, scatter.scale);
+        let (yaw, scale) = (scatter.between(yaw).to_radians(), scatter.between(scale));
+        spots.push(Transform {
+            translation: below.position(),
+            rotation: Quat::from_rotation_y(yaw),
+            scale: Vec3::splat(scale),
+        });
+    }
+    if !spots.is_empty() {
+        commands.trigger(triggers::Scatter(scatter.source.clone(), spots))
+    }
+}
+
+pub fn spawn(
+    trigger: Trigger<triggers::Scatter>,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    appdata: Res<AppData>,
+    content: Res<Content>,
+    consts: Res<Constants>,
+    mut registry: ResMut<Registry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<unlit::Unlit>>,
+    mut images: ResMut<Assets<Image>>,
+    mut map: NonSendMut<Map>,
+    actors: Query<&actor::Actor>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+) {
+    let triggers::Scatter(source, spots) = trigger.event();
+    let Some((map, _, export_names, _)) = &mut map.0 else {
+        return;
+    };
+    // the source may have been deleted or its tab closed since the brush was started
+    let actors: Vec<_> = source
+        .iter()
+        .filter_map(|entity| actors.get(*entity).ok())
+        .collect();
+    let roots: Vec<_> = actors
+        .iter()
+        .filter(|actor| actor.parent(map).is_none())
+        .map(|actor| actor.transform(map).translation)
+        .collect();
+    if roots.is_empty() {
+        notif.send(Notif {
+            message: "the actors being scattered are gone".into(),
+            kind: Error,
+        });
+        return;
+    }
+    let centre = roots.iter().sum::<Vec3>() / roots.len() as f32;
+    for entity in selected.iter() {
+        outliner::deselect(&mut commands, &mut cubes, &consts, entity);
+    }
+    let mut batch = Vec::new();
+    for spot in spots {
+        let copy = action::stamp(&mut commands, &mut notif, map, export_names, &actors);
+        let offset = Transform {
+            translation: spot.translation - centre,
+            ..*spot
+        };
+        action::arrange(&mut commands, map, &copy, centre, offset);
+        batch.extend(copy);
+    }
+    dialog::spawn_new(
+        map,
+        batch,
+        &mut commands,
+        &mut registry,
+        &content,
+        &appdata,
+        &consts,
+        &mut meshes,
+        &mut materials,
+        &mut images,
+        &mut notif,
+    );
+}
@@ -0,0 +1,582 @@
+use super::props::{find, find_mut, set_float, set_vector, to_bevy, vector};
+use super::*;
+use bevy::math::DVec3;
+use unreal_asset::{
+    cast,
+    exports::{normal_export::NormalExport, ExportNormalTrait},
+    properties::{int_property::BytePropertyValue, Property},
+    reader::archive_trait::ArchiveTrait,
+    types::PackageIndex,
+};
+
+/// how many lines each segment of a spline is drawn with
+const STEPS: usize = 16;
+
+/// a spline component rendered as a child of its actor
+#[derive(Component)]
+pub struct Curve(pub usize);
+
+/// a handle for the spline point at this index of the parent curve
+#[derive(Component)]
+pub struct Point(pub usize);
+
+/// EInterpCurveMode
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mode {
+    Linear,
+    CurveAuto,
+    Constant,
+    CurveUser,
+    CurveBreak,
+    CurveAutoClamped,
+}
+
+impl Mode {
+    fn read(prop: &Property) -> Self {
+        let name = match prop {
+            Property::ByteProperty(byte) => match &byte.value {
+                BytePropertyValue::Byte(byte) => {
+                    return match byte {
+                        1 => Self::CurveAuto,
+                        2 => Self::Constant,
+                        3 => Self::CurveUser,
+                        4 => Self::CurveBreak,
+                        5 => Self::CurveAutoClamped,
+                        _ => Self::Linear,
+                    }
+                }
+                BytePropertyValue::FName(name) => name.get_owned_content(),
+            },
+            Property::EnumProperty(enm) => enm
+                .value
+                .as_ref()
+                .map(|name| name.get_owned_content())
+                .unwrap_or_default(),
+            _ => return Self::Linear,
+        };
+        match name.rsplit("::").next().unwrap_or_default() {
+            "CIM_CurveAuto" => Self::CurveAuto,
+            "CIM_Constant" => Self::Constant,
+            "CIM_CurveUser" => Self::CurveUser,
+            "CIM_CurveBreak" => Self::CurveBreak,
+            "CIM_CurveAutoClamped" => Self::CurveAutoClamped,
+            _ => Self::Linear,
+        }
+    }
+
+    fn auto(self) -> bool {
+        matches!(self, Self::CurveAuto | Self::CurveAutoClamped)
+    }
+}
+
+/// a point on the position curve in unreal space
+#[derive(Clone, Copy)]
+pub struct Key {
+    pub key: f32,
+    pub pos: DVec3,
+    pub arrive: DVec3,
+    pub leave: DVec3,
+    pub mode: Mode,
+}
+
+impl Key {
+    fn read(props: &[Property]) -> Self {
+        Self {
+            key: props::float(props, "InVal").unwrap_or_default(),
+            pos: vector(props, "OutVal").unwrap_or_default(),
+            arrive: vector(props, "ArriveTangent").unwrap_or_default(),
+            leave: vector(props, "LeaveTangent").unwrap_or_default(),
+            mode: find(props, "InterpMode")
+                .map(Mode::read)
+                .unwrap_or(Mode::Linear),
+        }
+    }
+}
+
+pub struct Spline {
+    pub keys: Vec<Key>,
+    pub looped: bool,
+    pub offset: f32,
+}
+
+impl Spline {
+    /// reads the position curve of a spline component
+    pub fn read(asset: &Asset, export: usize) -> Option<Self> {
+        let curve = asset.asset_data.exports[export]
+            .get_normal_export()
+            .and_then(|norm| find(&norm.properties, "SplineCurves"))
+            .and_then(|prop| cast!(Property, StructProperty, prop))
+            .and_then(|curves| find(&curves.value, "Position"))
+            .and_then(|prop| cast!(Property, StructProperty, prop))?;
+        let keys = find(&curve.value, "Points")
+            .and_then(|prop| cast!(Property, ArrayProperty, prop))?
+            .value
+            .iter()
+            .filter_map(|prop| cast!(Property, StructProperty, prop))
+            .map(|point| Key::read(&point.value))
+            .collect();
+        Some(Self {
+            keys,
+            looped: find(&curve.value, "bIsLooped")
+                .and_then(|prop| cast!(Property, BoolProperty, prop))
+                .is_some_and(|bool| bool.value),
+            offset: props::float(&curve.value, "LoopKeyOffset").unwrap_or_default(),
+        })
+    }
+
+    /// every pair of points between which the curve is interpolated
+    fn segments(&self) -> Vec<(Key, Key)> {
+        let mut segments: Vec<_> = self
+            .keys
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect();
+        if let (true, Some(first), Some(last)) = (self.looped, self.keys.first(), self.keys.last())
+        {
+            segments.push((
+                *last,
+                Key {
+                    key: last.key + self.offset,
+                    ..*first
+                },
+            ))
+        }
+        segments
+    }
+
+    /// samples the curve into a line strip in bevy space
+    pub fn sample(&self) -> Vec<Vec3> {
+        let mut points: Vec<_> = self
+            .segments()
+            .into_iter()
+            .flat_map(|(a, b)| (0..STEPS).map(move |i| eval(&a, &b, i as f64 / STEPS as f64)))
+            .map(to_bevy)
+            .collect();
+        match self.looped {
+            true => points.extend(self.keys.first().map(|key| to_bevy(key.pos))),
+            false => points.extend(self.keys.last().map(|key| to_bevy(key.pos))),
+        }
+        points
+    }
+
+    /// recalculates tangents for automatic points like the engine does
+    fn auto_tangents(&mut self) {
+        let len = self.keys.len();
+        for i in 0..len {
+            if !self.keys[i].mode.auto() {
+                continue;
+            }
+            let (prev_key, prev) = match i {
+                0 if self.looped && len > 1 => {
+                    (self.keys[0].key - self.offset, self.keys[len - 1].pos)
+                }
+                0 => (self.keys[0].key, self.keys[0].pos),
+                i => (self.keys[i - 1].key, self.keys[i - 1].pos),
+            };
+            let (next_key, next) = match i + 1 == len {
+                true if self.looped && len > 1 => {
+                    (self.keys[i].key + self.offset, self.keys[0].pos)
+                }
+                true => (self.keys[i].key, self.keys[i].pos),
+                false => (self.keys[i + 1].key, self.keys[i + 1].pos),
+            };
+            let tangent = (next - prev) / ((next_key - prev_key) as f64).max(1e-4);
+            self.keys[i].arrive = tangent;
+            self.keys[i].leave = tangent;
+        }
+    }
+
+    /// the distance along a segment up to the given alpha
+    fn length(a: &Key, b: &Key, alpha: f64) -> f64 {
+        let mut prev = a.pos;
+        let mut length = 0.0;
+        for i in 1..=STEPS * 2 {
+            let next = eval(a, b, alpha * i as f64 / (STEPS * 2) as f64);
+            length += prev.distance(next);
+            prev = next;
+        }
+        length
+    }
+}
+
+/// FInterpCurve::Eval for a single segment
+fn eval(a: &Key, b: &Key, alpha: f64) -> DVec3 {
+    let diff = (b.key - a.key) as f64;
+    if diff <= 0.0 {
+        return a.pos;
+    }
+    match a.mode {
+        Mode::Constant => a.pos,
+        Mode::Linear => a.pos.lerp(b.pos, alpha),
+        _ => hermite(a.pos, a.leave * diff, b.pos, b.arrive * diff, alpha),
+    }
+}
+
+/// FMath::CubicInterp
+fn hermite(p0: DVec3, t0: DVec3, p1: DVec3, t1: DVec3, a: f64) -> DVec3 {
+    let a2 = a * a;
+    let a3 = a2 * a;
+    p0 * (2.0 * a3 - 3.0 * a2 + 1.0)
+        + t0 * (a3 - 2.0 * a2 + a)
+        + t1 * (a3 - a2)
+        + p1 * (-2.0 * a3 + 3.0 * a2)
+}
+
+#[test]
+fn interpolation() {
+    let a = Key {
+        key: 0.0,
+        pos: DVec3::ZERO,
+        arrive: DVec3::X * 100.0,
+        leave: DVec3::X * 100.0,
+        mode: Mode::CurveAuto,
+    };
+    let b = Key {
+        key: 1.0,
+        pos: DVec3::X * 100.0,
+        ..a
+    };
+    // straight tangents along the segment should interpolate linearly
+    assert!(eval(&a, &b, 0.5).distance(DVec3::X * 50.0) < 1e-9);
+    assert_eq!(eval(&a, &b, 0.0), a.pos);
+    assert_eq!(eval(&a, &b, 1.0), b.pos);
+    assert!((Spline::length(&a, &b, 1.0) - 100.0).abs() < 1e-6);
+}
+
+/// gets the points array of one of the component's curves
+fn points_mut<'a>(
+    norm: &'a mut NormalExport<PackageIndex>,
+    curve: &str,
+) -> Option<&'a mut Vec<Property>> {
+    let Some(Property::StructProperty(curves)) = find_mut(&mut norm.properties, "SplineCurves")
+    else {
+        return None;
+    };
+    let Some(Property::StructProperty(curve)) = find_mut(&mut curves.value, curve) else {
+        return None;
+    };
+    let Some(Property::ArrayProperty(points)) = find_mut(&mut curve.value, "Points") else {
+        return None;
+    };
+    Some(&mut points.value)
+}
+
+/// writes keys, tangents and the reparam table back to the component
+fn write(asset: &mut Asset, export: usize, mut spline: Spline) {
+    spline.auto_tangents();
+    let mut names = asset.get_name_map();
+    let steps = asset.asset_data.exports[export]
+        .get_normal_export()
+        .and_then(|norm| find(&norm.properties, "ReparamStepsPerSegment"))
+        .and_then(|prop| cast!(Property, IntProperty, prop))
+        .map(|int| int.value.max(1) as usize)
+        .unwrap_or(10);
+    let Some(norm) = asset.asset_data.exports[export].get_normal_export_mut() else {
+        return;
+    };
+    for curve in ["Position", "Rotation", "Scale"] {
+        let Some(points) = points_mut(norm, curve) else {
+            continue;
+        };
+        for (i, point) in points.iter_mut().enumerate() {
+            let Property::StructProperty(point) = point else {
+                continue;
+            };
+            let Some(key) = spline.keys.get(i) else {
+                continue;
+            };
+            set_float(&mut point.value, "InVal", key.key, &mut names);
+            if curve == "Position" {
+                set_vector(&mut point.value, "OutVal", key.pos, &mut names);
+                set_vector(&mut point.value, "ArriveTangent", key.arrive, &mut names);
+                set_vector(&mut point.value, "LeaveTangent", key.leave, &mut names);
+            }
+        }
+    }
+    // USplineComponent::UpdateSpline
+    let Some(table) = points_mut(norm, "ReparamTable") else {
+        return;
+    };
+    let Some(template) = table.first().cloned() else {
+        return;
+    };
+    let segments = spline.segments();
+    let mut entries = Vec::with_capacity(segments.len() * steps + 1);
+    let mut accumulated = 0.0;
+    for (i, (a, b)) in segments.iter().enumerate() {
+        for step in 0..steps {
+            let param = step as f64 / steps as f64;
+            entries.push((accumulated + Spline::length(a, b, param), i as f64 + param));
+        }
+        accumulated += Spline::length(a, b, 1.0);
+    }
+    entries.push((accumulated, segments.len() as f64));
+    table.clear();
+    for (length, key) in entries {
+        let mut entry = template.clone();
+        if let Property::StructProperty(struc) = &mut entry {
+            set_float(&mut struc.value, "InVal", length as f32, &mut names);
+            set_float(&mut struc.value, "OutVal", key as f32, &mut names);
+        }
+        table.push(entry);
+    }
+}
+
+/// moves a point by an offset in the component's space
+pub fn translate(asset: &mut Asset, export: usize, index: usize, offset: Vec3) {
+    let Some(mut spline) = Spline::read(asset, export) else {
+        return;
+    };
+    let Some(key) = spline.keys.get_mut(index) else {
+        return;
+    };
+    key.pos += bevy::math::dvec3(offset.x as f64, offset.z as f64, offset.y as f64) * 100.0;
+    write(asset, export, spline)
+}
+
+/// inserts a point halfway to the next one or past the end
+pub fn insert(asset: &mut Asset, export: usize, index: usize) {
+    let Some(mut spline) = Spline::read(asset, export) else {
+        return;
+    };
+    let Some(&point) = spline.keys.get(index) else {
+        return;
+    };
+    // other points keep their keys so the timing along the spline doesn't change
+    let key = match (spline.keys.get(index + 1), index.checked_sub(1)) {
+        (Some(next), _) => Key {
+            key: (point.key + next.key) / 2.0,
+            pos: eval(&point, next, 0.5),
+            ..point
+        },
+        // past the end keeps the spacing of the last segment
+        (None, prev) => Key {
+            key: point.key
+                + prev
+                    .map(|prev| point.key - spline.keys[prev].key)
+                    .filter(|step| *step > 0.0)
+                    .unwrap_or(1.0),
+            ..point
+        },
+    };
+    let Some(norm) = asset.asset_data.exports[export].get_normal_export_mut() else {
+        return;
+    };
+    for curve in ["Position", "Rotation", "Scale"] {
+        if let Some(points) = points_mut(norm, curve) {
+            if let Some(point) = points.get(index).cloned() {
+                points.insert(index + 1, point)
+            }
+        }
+    }
+    spline.keys.insert(index + 1, key);
+    write(asset, export, spline)
+}
+
+/// removes a point if it isn't one of the last two
+pub fn remove(asset: &mut Asset, export: usize, index: usize) -> bool {
+    let Some(mut spline) = Spline::read(asset, export) else {
+        return false;
+    };
+    if spline.keys.len() <= 2 {
+        return false;
+    }
+    let Some(norm) = asset.asset_data.exports[export].get_normal_export_mut() else {
+        return false;
+    };
+    for curve in ["Position", "Rotation", "Scale"] {
+        if let Some(points) = points_mut(norm, curve) {
+            if index < points.len() {
+                points.remove(index);
+            }
+        }
+    }
+    if index < spline.keys.len() {
+        spline.keys.remove(index);
+    }
+    write(asset, export, spline);
+    true
+}
+
+fn mesh(spline: &Spline) -> Mesh {
+    Mesh::new(
+        bevy::render::render_resource::PrimitiveTopology::LineStrip,
+        default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, spline.sample())
+}
+
+/// draws the spline components of newly spawned actors
+pub fn spawn(
+    mut commands: Commands,
+    map: NonSend<Map>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    consts: Res<Constants>,
+    added: Query<(Entity, &actor::Actor), Added<actor::Actor>>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    for (entity, actor) in added.iter() {
+        for export in actor.components(map) {
+            let Some(spline) = Spline::read(map, export) else {
+                continue;
+            };
+            let transform = match export == actor.root() {
+                true => Transform::IDENTITY,
+                false => actor::transform_of(map, export),
+            };
+            let mesh = meshes.add(mesh(&spline));
+            commands.entity(entity).with_children(|parent| {
+                parent.spawn((
+                    Curve(export),
+                    MaterialMeshBundle {
+                        mesh,
+                        material: consts.unselected.clone_weak(),
+                        transform,
+                        ..default()
+                    },
+                    bevy::pbr::wireframe::NoWireframe,
+                ));
+            });
+        }
+    }
+}
+
+/// shows point handles only while the spline's actor is selected
+pub fn handles(
+    mut commands: Commands,
+    map: NonSend<Map>,
+    consts: Res<Constants>,
+    curves: Query<(Entity, &Curve, &Parent, Option<&Children>)>,
+    selected: Query<(), With<actor::Selected>>,
+    points: Query<(), With<Point>>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    for (entity, curve, parent, children) in curves.iter() {
+        let shown = children.is_some_and(|children| children.iter().any(|c| points.contains(*c)));
+        match (selected.contains(parent.get()), shown) {
+            (true, false) => {
+                let Some(spline) = Spline::read(map, curve.0) else {
+                    continue;
+                };
+                commands.entity(entity).with_children(|parent| {
+                    for (i, key) in spline.keys.iter().enumerate() {
+                        spawn_point(parent, &consts, i, key);
+                    }
+                });
+            }
+            (false, true) => {
+                for child in children.into_iter().flatten() {
+                    if points.contains(*child) {
+                        commands.entity(*child).despawn_recursive();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn spawn_point(parent: &mut ChildBuilder, consts: &Constants, i: usize, key: &Key) {
+    parent
+        .spawn((
+            Point(i),
+            MaterialMeshBundle {
+                mesh: consts.cube.clone_weak(),
+                material: consts.selected.clone_weak(),
+                transform: Transform::from_translation(to_bevy(key.pos))
+                    .with_scale(Vec3::splat(0.25)),
+                ..default()
+            },
+            bevy::pbr::wireframe::NoWireframe,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                consts.bounds.clone_weak(),
+                SpatialBundle {
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+                bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
+            ));
+        });
+}
+
+/// rebuilds a spline's line strip and handles after it's edited
+pub fn refresh(
+    trigger: Trigger<triggers::RefreshSpline>,
+    mut commands: Commands,
+    map: NonSend<Map>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    curves: Query<(&Curve, &Handle<Mesh>, Option<&Children>)>,
+    mut points: Query<(&Point, &mut Transform)>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    let Ok((curve, handle, children)) = curves.get(trigger.event().0) else {
+        return;
+    };
+    let Some(spline) = Spline::read(map, curve.0) else {
+        return;
+    };
+    if let Some(mesh) = meshes.get_mut(handle) {
+        *mesh = self::mesh(&spline)
+    }
+    let handles: Vec<_> = children
+        .into_iter()
+        .flatten()
+        .filter(|child| points.contains(**child))
+        .copied()
+        .collect();
+    match handles.len() == spline.keys.len() {
+        true => {
+            for entity in handles {
+                if let Ok((point, mut transform)) = points.get_mut(entity) {
+                    transform.translation = to_bevy(spline.keys[point.0].pos)
+                }
+            }
+        }
+        // the handles system will respawn them
+        false => {
+            for entity in handles {
+                commands.entity(entity).despawn_recursive()
+            }
+        }
+    }
+}
+
+pub fn insert_point(
+    trigger: Trigger<triggers::InsertPoint>,
+    mut commands: Commands,
+    mut map: NonSendMut<Map>,
+    curves: Query<&Curve>,
+) {
+    let Some((map, ..)) = &mut map.0 else { return };
+    let &triggers::InsertPoint(entity, index) = trigger.event();
+    let Ok(curve) = curves.get(entity) else {
+        return;
+    };
+    insert(map, curve.0, index);
+    commands.trigger(triggers::RefreshSpline(entity));
+}
+
+pub fn delete_point(
+    trigger: Trigger<triggers::DeletePoint>,
+    mut commands: Commands,
+    mut notif: EventWriter<Notif>,
+    mut map: NonSendMut<Map>,
+    curves: Query<&Curve>,
+) {
+    let Some((map, ..)) = &mut map.0 else { return };
+    let &triggers::DeletePoint(entity, index) = trigger.event();
+    let Ok(curve) = curves.get(entity) else {
+        return;
+    };
+    match remove(map, curve.0, index) {
+        true => commands.trigger(triggers::RefreshSpline(entity)),
+        false => {
+            notif.send(Notif {
+                message: "splines need at least two points".into(),
+                kind: Warning,
+            });
+        }
+    }
+}
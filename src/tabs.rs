@@ -0,0 +1,297 @@
+use super::*;
+use std::path::{Path, PathBuf};
+
+// every open map gets a tab but only the shown one lives in Map with actors spawned
+// the rest are stashed with where the camera was, what was selected and their windows
+
+pub struct Stash {
+    pub map: (Asset, Option<PathBuf>, Vec<String>, Vec<String>),
+    // the camera's eye and target
+    pub camera: (Vec3, Vec3),
+    // exports of the selected actors
+    pub selected: Vec<usize>,
+    // the windows which describe this map
+    panels: (
+        refs::Graph,
+        inspector::Inspector,
+        validate::Validator,
+        diff::Differ,
+    ),
+    // the map this one was picking actors to transplant from
+    transplant: Transplant,
+}
+
+pub struct Tab {
+    pub name: String,
+    // where it was opened from or last saved to so the same file isn't opened twice
+    pub file: Option<PathBuf>,
+    // edited since it was opened or saved
+    pub dirty: bool,
+    // none for the shown tab
+    pub stash: Option<Stash>,
+}
+
+#[derive(Default)]
+pub struct Tabs {
+    pub tabs: Vec<Tab>,
+    pub active: usize,
+}
+
+/// what goes into a stash alongside the shown map
+#[derive(bevy::ecs::system::SystemParam)]
+pub struct Shown<'w, 's> {
+    camera: Query<'w, 's, &'static mut smooth_bevy_cameras::LookTransform, With<Camera3d>>,
+    selected: Query<'w, 's, &'static actor::Actor, With<actor::Selected>>,
+    graph: ResMut<'w, refs::Graph>,
+    inspector: ResMut<'w, inspector::Inspector>,
+    validator: ResMut<'w, validate::Validator>,
+    differ: ResMut<'w, diff::Differ>,
+    library: ResMut<'w, prefab::Library>,
+    transplant: NonSendMut<'w, Transplant>,
+}
+
+impl Shown<'_, '_> {
+    /// puts a stash's camera and windows back
+    pub fn restore(&mut self, stash: &mut Stash) {
+        let mut look = self.camera.single_mut();
+        (look.eye, look.target) = stash.camera;
+        (*self.graph, *self.inspector, *self.validator, *self.differ) =
+            std::mem::take(&mut stash.panels);
+        *self.transplant = std::mem::take(&mut stash.transplant);
+        self.library.disarm()
+    }
+}
+
+impl Tabs {
+    /// marks the shown map as edited
+    pub fn edit(&mut self) {
+        if let Some(tab) = self.tabs.get_mut(self.active) {
+            tab.dirty = true
+        }
+    }
+
+    /// marks the shown map as saved under a path
+    pub fn saved(&mut self, path: &Path) {
+        if let Some(tab) = self.tabs.get_mut(self.active) {
+            tab.dirty = false;
+            tab.name = title(path.to_str().unwrap_or_default());
+            tab.file = Some(path.to_path_buf());
+        }
+    }
+
+    /// moves the shown map into its tab
+    pub fn stash(&mut self, map: &mut Map, shown: &mut Shown) {
+        // the windows are cleared even with nothing to stash so a closed map's don't linger
+        let panels = (
+            std::mem::take(&mut *shown.graph),
+            std::mem::take(&mut *shown.inspector),
+            std::mem::take(&mut *shown.validator),
+            std::mem::take(&mut *shown.differ),
+        );
+        let transplant = std::mem::take(&mut *shown.transplant);
+        shown.library.disarm();
+        let Some(tab) = self.tabs.get_mut(self.active) else {
+            return;
+        };
+        if let Some(map) = map.0.take() {
+            let camera = shown.camera.single();
+            tab.stash = Some(Stash {
+                map,
+                camera: (camera.eye, camera.target),
+                selected: shown.selected.iter().map(|actor| actor.export).collect(),
+                panels,
+                transplant,
+            })
+        }
+    }
+
+    /// the tab a file is already open in
+    pub fn find(&self, file: &Path) -> Option<usize> {
+        self.tabs.iter().position(|tab| {
+            tab.file.as_deref().is_some_and(|open| {
+                open == file
+                    || std::fs::canonicalize(open).is_ok_and(|open| {
+                        std::fs::canonicalize(file).is_ok_and(|file| open == file)
+                    })
+            })
+        })
+    }
+
+    /// stashes the shown map and adds a tab for one which is about to be opened
+    pub fn open(&mut self, map: &mut Map, shown: &mut Shown, name: &str, file: Option<PathBuf>) {
+        self.stash(map, shown);
+        self.tabs.push(Tab {
+            name: title(name),
+            file,
+            dirty: false,
+            stash: None,
+        });
+        self.active = self.tabs.len() - 1;
+    }
+}
+
+/// the file name without the folders or extension
+pub fn title(name: &str) -> String {
+    name.rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(name)
+        .trim_end_matches(".umap")
+        .to_string()
+}
+
+/// the generic edits which dirty the shown map
+pub fn edited<E: Event>(_: Trigger<E>, mut tabs: NonSendMut<Tabs>) {
+    tabs.edit()
+}
+
+/// dirties the shown map when selected actors are moved
+pub fn track(
+    mut tabs: NonSendMut<Tabs>,
+    moved: Query<Ref<Transform>, (With<actor::Actor>, With<actor::Selected>)>,
+) {
+    if moved
+        .iter()
+        .any(|moved| moved.is_changed() && !moved.is_added())
+    {
+        tabs.edit()
+    }
+}
+
+pub fn bar(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    hidden: Res<Hidden>,
+    tabs: NonSend<Tabs>,
+) {
+    if hidden.0 || tabs.tabs.is_empty() {
+        return;
+    }
+    egui::TopBottomPanel::top("tabs").show(ctx.ctx_mut(), |ui| {
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            ui.horizontal(|ui| {
+                for (i, tab) in tabs.tabs.iter().enumerate() {
+                    let label = match tab.dirty {
+                        true => format!("{} ●", tab.name),
+                        false => tab.name.clone(),
+                    };
+                    let response = ui.selectable_label(i == tabs.active, label);
+                    if response.clicked() {
+                        commands.trigger(triggers::SwitchTab(i))
+                    }
+                    // outliner rows can be dropped on other tabs to copy actors into them
+                    if i != tabs.active {
+                        if let Some(entity) = response.dnd_release_payload::<Entity>() {
+                            commands.trigger(triggers::SendTo(i, *entity))
+                        }
+                    }
+                    if ui.small_button("x").on_hover_text("close").clicked() {
+                        commands.trigger(triggers::CloseTab(i))
+                    }
+                    ui.separator();
+                }
+            })
+        })
+    });
+}
+
+/// transplants the dragged actors into another tab's map
+pub fn send(
+    trigger: Trigger<triggers::SendTo>,
+    mut notif: EventWriter<Notif>,
+    mut tabs: NonSendMut<Tabs>,
+    map: NonSend<Map>,
+    actors: Query<&actor::Actor>,
+    selected: Query<&actor::Actor, With<actor::Selected>>,
+) {
+    let triggers::SendTo(i, entity) = *trigger.event();
+    let Some((donor, ..)) = &map.0 else { return };
+    // a selected row brings the rest of the selection with it
+    let sent: Vec<_> = match selected.get(entity) {
+        Ok(_) => selected.iter().collect(),
+        Err(_) => actors.get(entity).into_iter().collect(),
+    };
+    let Some(tab) = tabs.tabs.get_mut(i) else {
+        return;
+    };
+    let Some(Stash {
+        map: (recipient, _, export_names, import_names),
+        selected,
+        ..
+    }) = &mut tab.stash
+    else {
+        return;
+    };
+    selected.clear();
+    for (actor, landed) in sent.iter().zip(actor::transplant_group(
+        &sent,
+        recipient,
+        donor,
+        export_names,
+        import_names,
+    )) {
+        let len = match landed {
+            Ok(len) => len,
+            Err(problems) => {
+                notif.send(Notif {
+                    message: format!("couldn't copy {}:\n{}", actor.name, problems.join("\n")),
+                    kind: Error,
+                });
+                continue;
+            }
+        };
+        let insert = unreal_asset::types::PackageIndex::new(len as i32 + 1);
+        if let Ok((_, new)) = actor::Actor::new(recipient, insert) {
+            export_names[len] = new.name.clone();
+        }
+        selected.push(len);
+    }
+    if selected.is_empty() {
+        return;
+    }
+    tab.dirty = true;
+    notif.send(Notif {
+        message: format!("copied {} actors into {}", selected.len(), tab.name),
+        kind: Success,
+    });
+}
+
+pub fn close(
+    trigger: Trigger<triggers::CloseTab>,
+    mut commands: Commands,
+    mut tabs: NonSendMut<Tabs>,
+    mut map: NonSendMut<Map>,
+    actors: Query<Entity, With<actor::Actor>>,
+) {
+    let i = trigger.event().0;
+    let Some(tab) = tabs.tabs.get(i) else { return };
+    if tab.dirty
+        && !matches!(
+            rfd::MessageDialog::new()
+                .set_title("unsaved changes")
+                .set_description(format!("close {} without saving?", tab.name))
+                .set_buttons(rfd::MessageButtons::YesNo)
+                .show(),
+            rfd::MessageDialogResult::Yes
+        )
+    {
+        return;
+    }
+    tabs.tabs.remove(i);
+    if i != tabs.active {
+        if i < tabs.active {
+            tabs.active -= 1
+        }
+        return;
+    }
+    map.0 = None;
+    for actor in actors.iter() {
+        commands.entity(actor).despawn_recursive();
+    }
+    if tabs.tabs.is_empty() {
+        tabs.active = 0;
+        return;
+    }
+    // there's nothing shown to stash so point past the end before switching
+    tabs.active = tabs.tabs.len();
+    commands.trigger(triggers::SwitchTab(i.min(tabs.tabs.len() - 1)))
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+pub struct TintPlugin;
+
+impl Plugin for TintPlugin {
+    fn build(&self, app: &mut App) {
+        use bevy::asset::embedded_asset;
+        embedded_asset!(app, "tint.wgsl");
+        app.add_plugins(MaterialPlugin::<Tint>::default());
+    }
+}
+
+#[derive(bevy::render::render_resource::AsBindGroup, Asset, Reflect, Clone)]
+pub struct Tint {
+    #[uniform(0)]
+    pub colour: LinearRgba,
+}
+
+impl Material for Tint {
+    fn fragment_shader() -> bevy::render::render_resource::ShaderRef {
+        "embedded://stove/tint.wgsl".into()
+    }
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+    fn specialize(
+        _: &bevy::pbr::MaterialPipeline<Self>,
+        descriptor: &mut bevy::render::render_resource::RenderPipelineDescriptor,
+        _: &bevy::render::mesh::MeshVertexBufferLayoutRef,
+        _: bevy::pbr::MaterialPipelineKey<Self>,
+    ) -> Result<(), bevy::render::render_resource::SpecializedMeshPipelineError> {
+        // volumes should be visible from the inside too
+        descriptor.primitive.cull_mode = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+#import bevy_pbr::forward_io::VertexOutput
+
+@group(2) @binding(0) var<uniform> colour: vec4<f32>;
+
+@fragment
+fn fragment(
+    mesh: VertexOutput,
+) -> @location(0) vec4<f32> {
+    return colour;
+}
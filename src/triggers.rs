@@ -1,8 +1,9 @@
 use super::*;
 
 // actions
+// whether the copies are placed by the placement option rather than being dragged
 #[derive(Event)]
-pub struct Duplicate;
+pub struct Duplicate(pub bool);
 #[derive(Event)]
 pub struct Delete;
 #[derive(Event)]
@@ -12,6 +13,10 @@ pub struct Copy;
 #[derive(Event)]
 pub struct Paste;
 #[derive(Event)]
+pub struct CopyLocation;
+#[derive(Event)]
+pub struct PasteLocation;
+#[derive(Event)]
 pub struct Deselect;
 #[derive(Event)]
 pub struct Fullscreen;
@@ -19,6 +24,53 @@ pub struct Fullscreen;
 pub struct Hide;
 #[derive(Event)]
 pub struct LoadPaks;
+#[derive(Event)]
+pub struct References;
+#[derive(Event)]
+pub struct Inspector;
+#[derive(Event)]
+pub struct Validate;
+#[derive(Event)]
+pub struct Diff;
+#[derive(Event)]
+pub struct ExportText;
+#[derive(Event)]
+pub struct Patch;
+#[derive(Event)]
+pub struct Prefabs;
+#[derive(Event)]
+pub struct PlacePrefab(pub std::path::PathBuf, pub Vec3);
+#[derive(Event)]
+pub struct ArrayTool;
+#[derive(Event)]
+pub struct MakeArray;
+#[derive(Event)]
+pub struct ScatterTool;
+// the source actors and where each set of copies goes
+#[derive(Event)]
+pub struct Scatter(pub Vec<Entity>, pub Vec<Transform>);
+#[derive(Event)]
+pub struct ReplaceTool;
+#[derive(Event)]
+pub struct ReplaceSelected;
+#[derive(Event)]
+pub struct Record(pub patch::Op);
+
+// splines
+#[derive(Event)]
+pub struct RefreshSpline(pub Entity);
+#[derive(Event)]
+pub struct InsertPoint(pub Entity, pub usize);
+#[derive(Event)]
+pub struct DeletePoint(pub Entity, pub usize);
+
+// volumes
+#[derive(Event)]
+pub struct RefreshVolume(pub Entity);
+
+// materials
+#[derive(Event)]
+pub struct Retexture(pub Entity);
 
 // dialogs
 #[derive(Event)]
@@ -33,3 +85,17 @@ pub struct AddPak;
 pub struct TransplantFrom;
 #[derive(Event)]
 pub struct TransplantInto;
+#[derive(Event)]
+pub struct TransplantSelected;
+#[derive(Event)]
+pub struct DonorVersion(pub usize);
+#[derive(Event)]
+pub struct ApplyPatch;
+
+// tabs
+#[derive(Event)]
+pub struct SwitchTab(pub usize);
+#[derive(Event)]
+pub struct CloseTab(pub usize);
+#[derive(Event)]
+pub struct SendTo(pub usize, pub Entity);
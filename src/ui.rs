@@ -10,7 +10,8 @@ pub fn sidebar(
     mut transplant: NonSendMut<Transplant>,
     mut wire: ResMut<bevy::pbr::wireframe::WireframeConfig>,
     mut from_content: ResMut<FromContent>,
-    hidden: Res<Hidden>,
+    // bundled to stay within the parameter limit
+    (hidden, recorder, registry): (Res<Hidden>, Res<patch::Recorder>, Res<Registry>),
     consts: Res<Constants>,
     mut fps: ResMut<bevy_framepace::FramepaceSettings>,
     actors: Query<(Entity, &actor::Actor)>,
@@ -65,6 +66,42 @@ pub fn sidebar(
                     commands.trigger(triggers::SaveAs(true));
                     ui.close_menu();
                 }
+                if ui.button("validate").clicked() {
+                    commands.trigger(triggers::Validate);
+                    ui.close_menu();
+                }
+                if ui.button("compare with").clicked() {
+                    commands.trigger(triggers::Diff);
+                    ui.close_menu();
+                }
+                if ui.button("export as text").clicked() {
+                    commands.trigger(triggers::ExportText);
+                    ui.close_menu();
+                }
+                if ui.button("patch").clicked() {
+                    commands.trigger(triggers::Patch);
+                    ui.close_menu();
+                }
+                if ui.button("apply patch").clicked() {
+                    commands.trigger(triggers::ApplyPatch);
+                    ui.close_menu();
+                }
+                if ui.button("prefabs").clicked() {
+                    commands.trigger(triggers::Prefabs);
+                    ui.close_menu();
+                }
+                if ui.button("array").clicked() {
+                    commands.trigger(triggers::ArrayTool);
+                    ui.close_menu();
+                }
+                if ui.button("scatter").clicked() {
+                    commands.trigger(triggers::ScatterTool);
+                    ui.close_menu();
+                }
+                if ui.button("replace with").clicked() {
+                    commands.trigger(triggers::ReplaceTool);
+                    ui.close_menu();
+                }
             });
             egui::ComboBox::from_id_source("version").width(0.0)
                 .show_index(ui, &mut appdata.version, VERSIONS.len(), |i| VERSIONS[i].1.to_string());
@@ -135,6 +172,20 @@ pub fn sidebar(
                         wire.global = !wire.global
                     }
                 });
+                ui.horizontal(|ui| {
+                    ui.label("place copies:");
+                    egui::ComboBox::from_id_source("placement")
+                        .selected_text(match appdata.placement {
+                            Placement::Original => "where they were",
+                            Placement::Cursor => "at the cursor",
+                            Placement::Camera => "in front of the camera",
+                        })
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut appdata.placement, Placement::Original, "where they were");
+                            ui.selectable_value(&mut appdata.placement, Placement::Cursor, "at the cursor");
+                            ui.selectable_value(&mut appdata.placement, Placement::Camera, "in front of the camera");
+                        });
+                });
                 ui.horizontal(|ui| {
                     ui.label("cache assets:");
                     ui.add(egui::Checkbox::without_text(&mut appdata.cache));
@@ -203,90 +254,76 @@ pub fn sidebar(
         if map.0.is_none() {
             return;
         }
-        if ui.add(egui::TextEdit::singleline(&mut appdata.query).hint_text("🔎 search actors")).changed() {
+        let (search, select) = ui.horizontal(|ui| {
+            let select = ui.add_enabled(!appdata.query.trim().is_empty(), egui::Button::new("select")).on_hover_text("select all matches").clicked();
+            let search = ui.add(egui::TextEdit::singleline(&mut appdata.query).hint_text("🔎 search e.g. class:PointLight prop:Intensity>5000").desired_width(f32::INFINITY));
+            (search.changed() || (search.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter))), select)
+        }).inner;
+        if let (true, Some((map, ..))) = (search, &map.0) {
             for (entity, _) in matched.iter() {
                 commands.entity(entity).remove::<actor::Matched>();
             }
-            for (entity, actor) in actors.iter() {
-                if actor.name.to_ascii_lowercase().contains(&appdata.query.to_ascii_lowercase()) {
-                    commands.entity(entity).insert(actor::Matched);
+            let all: Vec<_> = actors.iter().collect();
+            let highlighted: Vec<_> = selected.iter().map(|(entity, ..)| entity).collect();
+            let ctx = query::Context { map, actors: &all, selected: &highlighted };
+            match query::parse(&appdata.query, &ctx) {
+                Ok(matcher) => {
+                    appdata.query_error = None;
+                    for (entity, actor) in all.iter() {
+                        if matcher.matches(map, actor, &registry) {
+                            commands.entity(*entity).insert(actor::Matched);
+                        }
+                    }
                 }
+                Err(e) => appdata.query_error = Some(e),
+            }
+        }
+        if let Some(e) = &appdata.query_error {
+            ui.colored_label(egui::Color32::LIGHT_RED, e);
+        }
+        if select {
+            for (entity, ..) in selected.iter() {
+                outliner::deselect(&mut commands, &mut cubes, &consts, entity);
+            }
+            for (entity, _) in matched.iter() {
+                outliner::select(&mut commands, &mut cubes, &consts, entity);
             }
         }
         ui.add_space(10.0);
-        egui::ScrollArea::both()
-            .id_source("actors")
-            .auto_shrink([false, true])
-            .max_height(ui.available_height() * 0.5)
-            .show_rows(
-                ui,
-                ui.text_style_height(&egui::TextStyle::Body),
-                match appdata.query.is_empty() {
-                    true => actors.iter().len(),
-                    false => matched.iter().len(),
-                },
-                |ui, range| ui.with_layout(egui::Layout::default().with_cross_justify(true), |ui| {
-                    let mut displayed: Vec<_> = match appdata.query.is_empty() {
-                        true => actors.iter().collect(),
-                        false => matched.iter().collect(),
-                    };
-                    displayed.sort_by_key(|(_, actor)| actor.export);
-                    for (entity, actor) in displayed.into_iter().skip(range.start).take(range.end - range.start) {
-                        let highlighted = selected.contains(entity);
-                        if ui.selectable_label(
-                            highlighted,
-                            &actor.display,
-                        )
-                        .on_hover_text(&actor.class)
-                        .clicked() {
-                            ui.input(|state| if !state.modifiers.shift && !state.modifiers.ctrl {
-                                for (entity, ..) in selected.iter() {
-                                    match cubes.get_mut(entity) {
-                                        Ok(mut mat) => {
-                                            commands.entity(entity).remove::<actor::Selected>();
-                                            *mat = consts.unselected.clone_weak();
-                                        },
-                                        Err(_) => {
-                                            commands.entity(entity).remove::<actor::SelectedBundle>();
-                                        },
-                                    }
-                                }
-                            });
-                            match highlighted {
-                                true => match cubes.get_mut(entity) {
-                                    Ok(mut mat) => {
-                                        commands.entity(entity).remove::<actor::Selected>();
-                                        *mat = consts.unselected.clone_weak();
-                                    },
-                                    Err(_) => {
-                                        commands.entity(entity).remove::<actor::SelectedBundle>();
-                                    },
-                                },
-                                // false if ui.input(|input| input.modifiers.shift) => todo!(),
-                                false => match cubes.get_mut(entity) {
-                                    Ok(mut mat) => {
-                                        commands.entity(entity).insert(actor::Selected);
-                                        *mat = consts.selected.clone_weak();
-                                    }
-                                    Err(_) => {
-                                        commands
-                                            .entity(entity)
-                                            .insert(actor::SelectedBundle::default());
-                                    }
-                                },
-                            };
-                        }
-                    }
-                })
-            );
+        if let Some((map, ..)) = &map.0 {
+            let displayed = match appdata.query.trim().is_empty() {
+                true => actors.iter().collect(),
+                false => matched.iter().collect(),
+            };
+            let highlighted: Vec<_> = selected.iter().map(|(entity, ..)| entity).collect();
+            appdata.outliner.show(ui, map, &mut commands, &consts, displayed, &highlighted, &mut cubes);
+        }
         ui.add_space(10.0);
-        if let (Ok((_, actor, mut transform)), Some((map, _, exports, imports))) = (selected.get_single_mut(), &mut map.0) {
+        if let (Ok((entity, actor, mut transform)), Some((map, _, exports, imports))) = (selected.get_single_mut(), &mut map.0) {
+            let materials = actor.materials(map);
+            // snapshots are only worth taking while they're recorded
+            let before = recorder.recording.then(|| patch::snapshot(map, actor));
             egui::ScrollArea::both()
                 .id_source("properties")
                 .auto_shrink([false; 2])
                 .show(ui, |ui| {
                     actor.show(map, ui, &mut transform, &exports, &imports);
                 });
+            if let Some(before) = before {
+                let (ops, added) = patch::changes(map, actor, &before);
+                for op in ops {
+                    commands.trigger(triggers::Record(op));
+                }
+                for property in added {
+                    notif.send(Notif {
+                        message: format!("{property} was added so it can't be recorded in the patch"),
+                        kind: egui_notify::ToastLevel::Warning
+                    });
+                }
+            }
+            if actor.materials(map) != materials {
+                commands.trigger(triggers::Retexture(entity));
+            }
         }
     });
     let mut open = true;
@@ -319,16 +356,27 @@ pub fn sidebar(
     if !open || clicked {
         from_content.0 = false
     }
-    let mut transplanted = None;
-    if let (Some((donor, others, selected)), Some((map, _, export_names, import_names))) =
-        (&mut transplant.0, &mut map.0)
-    {
+    if let (Some((_, _, others, selected, version)), Some(_)) = (&mut transplant.0, &map.0) {
         egui::Window::new("transplant actor")
             .anchor(egui::Align2::CENTER_CENTER, (0.0, 0.0))
             .resizable(false)
             .collapsible(false)
             .open(&mut open)
             .show(ctx.ctx_mut(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("donor version:");
+                    egui::ComboBox::from_id_source("donor version")
+                        .selected_text(VERSIONS[*version].1)
+                        .show_ui(ui, |ui| {
+                            for (i, (_, name)) in VERSIONS.iter().enumerate() {
+                                if ui.selectable_label(i == *version, *name).clicked()
+                                    && i != *version
+                                {
+                                    commands.trigger(triggers::DonorVersion(i))
+                                }
+                            }
+                        });
+                });
                 // putting the button below breaks the scroll area somehow
                 ui.add_enabled_ui(!selected.is_empty(), |ui| {
                     if ui
@@ -336,42 +384,7 @@ pub fn sidebar(
                         .inner
                         .clicked()
                     {
-                        let len = actors.iter().len();
-                        transplanted = Some(len..len + selected.len());
-                        for actor in selected.iter().map(|i| &others[*i]) {
-                            let len = map.asset_data.exports.len();
-                            let insert = unreal_asset::types::PackageIndex::new(len as i32 + 1);
-                            actor.transplant(map, donor, export_names, import_names);
-                            notif.send(Notif {
-                                message: format!("transplanted {}", actor.name),
-                                kind: Success,
-                            });
-                            // don't process mesh for transplanted actor for now
-                            let (_, actor) = actor::Actor::new(map, insert).unwrap();
-                            export_names[len] = actor.name.clone();
-                            commands
-                                .spawn((
-                                    actor::Selected,
-                                    MaterialMeshBundle {
-                                        mesh: consts.cube.clone_weak(),
-                                        material: consts.selected.clone_weak(),
-                                        transform: actor.transform(map),
-                                        ..default()
-                                    },
-                                    bevy::pbr::wireframe::NoWireframe,
-                                    actor,
-                                ))
-                                .with_children(|parent| {
-                                    parent.spawn((
-                                        consts.bounds.clone_weak(),
-                                        SpatialBundle {
-                                            visibility: Visibility::Hidden,
-                                            ..default()
-                                        },
-                                        bevy_mod_raycast::deferred::RaycastMesh::<()>::default(),
-                                    ));
-                                });
-                        }
+                        commands.trigger(triggers::TransplantSelected)
                     }
                 });
                 egui::ScrollArea::both().auto_shrink([false; 2]).show_rows(
@@ -413,7 +426,7 @@ pub fn sidebar(
                     },
                 );
             });
-        if transplanted.is_some() || !open {
+        if !open {
             transplant.0 = None
         }
     }
@@ -458,6 +471,8 @@ fn shortcuts(ui: &mut egui::Ui) {
             ("hide ui", "h"),
             ("select", "left-click"),
             ("deselect all", "escape"),
+            ("references", "r"),
+            ("package inspector", "i"),
         ],
     );
     section(
@@ -467,14 +482,25 @@ fn shortcuts(ui: &mut egui::Ui) {
             ("move", "shift + left-drag"),
             ("rotate", "shift + right-drag"),
             ("scale", "shift + middle-drag"),
-            ("copy location", "ctrl + c"),
-            ("paste location", "ctrl + v"),
-            ("duplicate", "alt + left-drag"),
+            ("copy", "ctrl + c"),
+            ("paste", "ctrl + v"),
+            ("copy location", "ctrl + shift + c"),
+            ("paste location", "ctrl + shift + v"),
+            ("duplicate", "ctrl + d"),
+            ("duplicate and drag", "alt + shift + left-drag"),
             ("delete", "delete"),
             ("lock x / y / z axis", "x / y / z"),
             ("lock x / y / z plane", "shift + x / y / z"),
         ],
     );
+    section(
+        "spline",
+        &[
+            ("move point", "left-drag"),
+            ("insert point", "alt + left-drag"),
+            ("delete point", "ctrl + left-click"),
+        ],
+    );
 }
 pub fn notifs(
     mut notif: EventReader<Notif>,
@@ -0,0 +1,377 @@
+use super::*;
+use unreal_asset::{
+    cast,
+    exports::{Export, ExportBaseTrait, ExportNormalTrait},
+    properties::PropertyDataTrait,
+    reader::archive_trait::ArchiveTrait,
+    types::{fname::FName, PackageIndex, PackageIndexTrait},
+};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Severity {
+    Warning,
+    // the game will crash or the package won't load
+    Fatal,
+}
+
+pub struct Finding {
+    pub severity: Severity,
+    pub object: PackageIndex,
+    pub message: String,
+}
+
+#[derive(Default, Resource)]
+pub struct Validator {
+    pub open: bool,
+    pub findings: Vec<Finding>,
+    // whether it's been run since the map was opened
+    ran: bool,
+    // a save refused over fatal problems and whether it was a save as
+    pub blocked: Option<bool>,
+    // lets the next save go through regardless
+    pub force: bool,
+}
+
+impl Validator {
+    pub fn report(&mut self, findings: Vec<Finding>) {
+        self.findings = findings;
+        self.ran = true;
+        self.blocked = None;
+        self.open = true;
+    }
+
+    pub fn fatal(&self) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == Severity::Fatal)
+            .count()
+    }
+}
+
+/// whether a backed name is actually in the name map
+fn covered(name: &FName, len: usize) -> bool {
+    match name {
+        FName::Backed { index, .. } => (0..len as i32).contains(index),
+        FName::Dummy { .. } => true,
+    }
+}
+
+/// checks a map for problems which would break it in-game
+pub fn check(map: &Asset) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut push = |severity, object, message: String| {
+        findings.push(Finding {
+            severity,
+            object,
+            message,
+        })
+    };
+    let exports = map.asset_data.exports.len() as i32;
+    let imports = map.imports.len() as i32;
+    let valid = |index: &PackageIndex| (-imports..=exports).contains(&index.index);
+    let names = map.get_name_map().get_ref().get_name_map_index_list().len();
+    // imports referenced by anything so unused ones can be reported
+    let mut used = vec![false; map.imports.len()];
+
+    for (i, import) in map.imports.iter().enumerate() {
+        let object = PackageIndex::new(-(i as i32) - 1);
+        match import.outer_index {
+            outer if !valid(&outer) => push(
+                Severity::Fatal,
+                object,
+                format!("outer {} is out of bounds", outer.index),
+            ),
+            outer if outer.is_export() => push(
+                Severity::Warning,
+                object,
+                "import is inside an export".into(),
+            ),
+            outer if outer.is_import() => used[-outer.index as usize - 1] = true,
+            _ => (),
+        }
+        for (what, name) in [
+            ("object name", &import.object_name),
+            ("class package", &import.class_package),
+            ("class name", &import.class_name),
+        ] {
+            if !covered(name, names) {
+                push(
+                    Severity::Fatal,
+                    object,
+                    format!("{what} isn't in the name map"),
+                )
+            }
+        }
+    }
+
+    let mut seen = std::collections::HashMap::new();
+    for (i, ex) in map.asset_data.exports.iter().enumerate() {
+        let object = PackageIndex::new(i as i32 + 1);
+        // the ref functions need mutable access so work on a copy
+        let mut copy = ex.clone();
+        let mut refs = Vec::new();
+        actor::on_export_refs(&mut copy, |index| refs.push(*index));
+        actor::on_import_refs(&mut copy, &mut |index| refs.push(*index));
+        refs.sort_by_key(|index| index.index);
+        refs.dedup();
+        for index in refs {
+            if !valid(&index) {
+                push(
+                    Severity::Fatal,
+                    object,
+                    format!("reference {} is out of bounds", index.index),
+                )
+            } else if index.is_import() {
+                used[-index.index as usize - 1] = true
+            }
+        }
+        let base = ex.get_base_export();
+        if !covered(&base.object_name, names) {
+            push(
+                Severity::Fatal,
+                object,
+                "object name isn't in the name map".into(),
+            );
+            continue;
+        }
+        let name = actor::full_name(&base.object_name);
+        if let Some(first) = seen.insert((base.outer_index.index, name.clone()), object) {
+            push(
+                Severity::Fatal,
+                object,
+                format!("{name} has the same name and outer as {}", first.index),
+            )
+        }
+        if let Some(norm) = ex.get_normal_export() {
+            let mut uncovered = 0;
+            for prop in norm.properties.iter() {
+                if !covered(&prop.get_name(), names) {
+                    uncovered += 1
+                }
+                props::visit(prop, &mut |prop| {
+                    if !covered(&prop.get_name(), names) {
+                        uncovered += 1
+                    }
+                });
+            }
+            if uncovered != 0 {
+                push(
+                    Severity::Fatal,
+                    object,
+                    format!("{uncovered} property names aren't in the name map"),
+                )
+            }
+        }
+    }
+
+    if let Some((i, level)) = map
+        .asset_data
+        .exports
+        .iter()
+        .enumerate()
+        .find_map(|(i, ex)| cast!(Export, LevelExport, ex).map(|level| (i, level)))
+    {
+        let object = PackageIndex::new(i as i32 + 1);
+        let deps = &level
+            .get_base_export()
+            .create_before_serialization_dependencies;
+        for actor in level.actors.iter().filter(|actor| actor.index != 0) {
+            if !actor.is_export() || actor.index > exports {
+                push(
+                    Severity::Fatal,
+                    object,
+                    format!("actor {} isn't an export", actor.index),
+                );
+                continue;
+            }
+            if !deps.contains(actor) {
+                push(
+                    Severity::Warning,
+                    *actor,
+                    "actor is missing from the level's dependencies".into(),
+                )
+            }
+            let outer = map.asset_data.exports[actor.index as usize - 1]
+                .get_base_export()
+                .outer_index;
+            if outer != object {
+                push(
+                    Severity::Warning,
+                    *actor,
+                    "actor isn't inside the level".into(),
+                )
+            }
+        }
+    }
+
+    for (i, _) in used.iter().enumerate().filter(|(_, used)| !**used) {
+        push(
+            Severity::Warning,
+            PackageIndex::new(-(i as i32) - 1),
+            "import isn't used by anything".into(),
+        )
+    }
+    findings
+}
+
+pub fn run(
+    _: Trigger<triggers::Validate>,
+    mut notif: EventWriter<Notif>,
+    mut validator: ResMut<Validator>,
+    map: NonSend<Map>,
+) {
+    let Some((map, ..)) = &map.0 else {
+        notif.send(Notif {
+            message: "no map to validate".into(),
+            kind: Error,
+        });
+        return;
+    };
+    validator.report(check(map));
+}
+
+pub fn panel(
+    mut ctx: bevy_egui::EguiContexts,
+    mut commands: Commands,
+    mut validator: ResMut<Validator>,
+    mut inspector: ResMut<inspector::Inspector>,
+    map: NonSend<Map>,
+    consts: Res<Constants>,
+    actors: Query<(Entity, &actor::Actor)>,
+    selected: Query<Entity, With<actor::Selected>>,
+    mut cubes: Query<&mut Handle<wire::Wire>>,
+) {
+    let Some((map, ..)) = &map.0 else {
+        validator.open = false;
+        validator.ran = false;
+        validator.findings.clear();
+        return;
+    };
+    if !validator.open {
+        return;
+    }
+    let actors: Vec<_> = actors.iter().collect();
+    let mut open = true;
+    let mut select = None;
+    let mut inspect = None;
+    let mut rerun = false;
+    let mut force = None;
+    egui::Window::new("validation")
+        .open(&mut open)
+        .default_width(350.0)
+        .show(ctx.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("⟳").on_hover_text("run again").clicked() {
+                    rerun = true
+                }
+                let fatal = validator.fatal();
+                ui.label(format!(
+                    "{fatal} fatal, {} warnings",
+                    validator.findings.len() - fatal
+                ));
+                if let Some(save_as) = validator.blocked {
+                    if ui
+                        .button("save anyway")
+                        .on_hover_text("the map may not load in-game")
+                        .clicked()
+                    {
+                        force = Some(save_as)
+                    }
+                }
+            });
+            if !validator.ran {
+                return;
+            }
+            if validator.findings.is_empty() {
+                ui.label("no problems found");
+                return;
+            }
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, true])
+                .show_rows(
+                    ui,
+                    ui.text_style_height(&egui::TextStyle::Body),
+                    validator.findings.len(),
+                    |ui, range| {
+                        for finding in &validator.findings[range] {
+                            ui.horizontal(|ui| {
+                                match finding.severity {
+                                    Severity::Fatal => ui.colored_label(egui::Color32::RED, "⊗"),
+                                    Severity::Warning => {
+                                        ui.colored_label(egui::Color32::YELLOW, "⚠")
+                                    }
+                                };
+                                if ui
+                                    .link(refs::name(map, finding.object))
+                                    .on_hover_text("inspect")
+                                    .clicked()
+                                {
+                                    inspect = Some(finding.object)
+                                }
+                                if let Some(entity) = refs::owner(map, finding.object, &actors) {
+                                    if ui.small_button("select").clicked() {
+                                        select = Some(entity)
+                                    }
+                                }
+                                ui.label(&finding.message);
+                            });
+                        }
+                    },
+                );
+        });
+    if rerun {
+        validator.report(check(map));
+    }
+    if let Some(save_as) = force {
+        validator.force = true;
+        validator.blocked = None;
+        commands.trigger(triggers::SaveAs(save_as));
+    }
+    if let Some(index) = inspect {
+        inspector.pick(index)
+    }
+    if let Some(entity) = select {
+        for old in selected.iter() {
+            outliner::deselect(&mut commands, &mut cubes, &consts, old);
+        }
+        outliner::select(&mut commands, &mut cubes, &consts, entity);
+        commands.trigger(triggers::Focus);
+    }
+    validator.open &= open;
+}
+
+#[test]
+fn problems() {
+    let clean = regression::sample();
+    let known = check(&clean).len();
+    let found = |map: &Asset, message: &str| {
+        check(map)
+            .iter()
+            .any(|finding| finding.message.contains(message))
+    };
+    let normal = (0..clean.asset_data.exports.len())
+        .find(|&i| clean.asset_data.exports[i].get_normal_export().is_some())
+        .unwrap();
+    // a reference past the end of the exports
+    let mut map = regression::sample();
+    regression::point(&mut map, normal, 999);
+    assert!(found(&map, "reference 1000 is out of bounds"));
+    // two exports with the same name under the same outer
+    let mut map = regression::sample();
+    let copy = map.asset_data.exports[normal].clone();
+    map.asset_data.exports.push(copy);
+    assert!(found(
+        &map,
+        &format!("has the same name and outer as {}", normal + 1)
+    ));
+    // an import nothing points at
+    let mut map = regression::sample();
+    let copy = map.imports[0].clone();
+    map.imports.push(copy);
+    let findings = check(&map);
+    assert!(findings.iter().any(
+        |finding| finding.object.index == -(map.imports.len() as i32)
+            && finding.message == "import isn't used by anything"
+    ));
+    assert_eq!(findings.len(), known + 1);
+}
@@ -0,0 +1,349 @@
+use super::props::{as_vector, find, float, set_float, set_vector, to_bevy, vector};
+use super::*;
+use bevy::math::DVec3;
+use std::f32::consts::{PI, TAU};
+use unreal_asset::{
+    cast,
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::Property,
+    reader::archive_trait::ArchiveTrait,
+};
+
+/// an actor whose root component is a collision shape or brush
+#[derive(Component)]
+pub struct Volume(pub usize);
+
+/// the translucent inside of a shape
+#[derive(Component)]
+pub struct Fill;
+
+/// the extents of a shape in unreal space
+#[derive(Clone, Copy)]
+pub enum Shape {
+    Box(DVec3),
+    Sphere(f64),
+    Capsule(f64, f64),
+    Brush(DVec3, DVec3),
+}
+
+impl Shape {
+    pub fn read(asset: &Asset, export: usize) -> Option<Self> {
+        let ex = &asset.asset_data.exports[export];
+        let class = asset
+            .get_import(ex.get_base_export().class_index)?
+            .object_name
+            .get_owned_content();
+        let props = &ex.get_normal_export()?.properties;
+        // defaults are from the component constructors since they're cut when unchanged
+        match class.as_str() {
+            "BoxComponent" => Some(Self::Box(
+                vector(props, "BoxExtent").unwrap_or(DVec3::splat(32.0)),
+            )),
+            "SphereComponent" => Some(Self::Sphere(
+                float(props, "SphereRadius").unwrap_or(32.0) as f64
+            )),
+            "CapsuleComponent" => Some(Self::Capsule(
+                float(props, "CapsuleRadius").unwrap_or(22.0) as f64,
+                float(props, "CapsuleHalfHeight").unwrap_or(44.0) as f64,
+            )),
+            // the default brush is a 200 unit cube
+            "BrushComponent" => Some(
+                brush(asset, props)
+                    .unwrap_or(Self::Brush(DVec3::splat(-100.0), DVec3::splat(100.0))),
+            ),
+            _ => None,
+        }
+    }
+
+    /// centre and size of the bounding box in bevy space
+    fn bounds(&self) -> (Vec3, Vec3) {
+        let (centre, size) = match *self {
+            Self::Box(extent) => (DVec3::ZERO, extent * 2.0),
+            Self::Sphere(radius) => (DVec3::ZERO, DVec3::splat(radius * 2.0)),
+            Self::Capsule(radius, half) => (
+                DVec3::ZERO,
+                bevy::math::dvec3(radius * 2.0, radius * 2.0, half * 2.0),
+            ),
+            Self::Brush(min, max) => ((min + max) / 2.0, max - min),
+        };
+        (to_bevy(centre), to_bevy(size))
+    }
+
+    fn lines(&self) -> Mesh {
+        let mut verts = Vec::new();
+        match *self {
+            Self::Box(..) | Self::Brush(..) => {
+                let (centre, size) = self.bounds();
+                let corner = |x: f32, y: f32, z: f32| centre + size * Vec3::new(x, y, z) * 0.5;
+                for (a, b) in [
+                    // bottom
+                    ((-1.0, -1.0, -1.0), (1.0, -1.0, -1.0)),
+                    ((1.0, -1.0, -1.0), (1.0, -1.0, 1.0)),
+                    ((1.0, -1.0, 1.0), (-1.0, -1.0, 1.0)),
+                    ((-1.0, -1.0, 1.0), (-1.0, -1.0, -1.0)),
+                    // top
+                    ((-1.0, 1.0, -1.0), (1.0, 1.0, -1.0)),
+                    ((1.0, 1.0, -1.0), (1.0, 1.0, 1.0)),
+                    ((1.0, 1.0, 1.0), (-1.0, 1.0, 1.0)),
+                    ((-1.0, 1.0, 1.0), (-1.0, 1.0, -1.0)),
+                    // sides
+                    ((-1.0, -1.0, -1.0), (-1.0, 1.0, -1.0)),
+                    ((1.0, -1.0, -1.0), (1.0, 1.0, -1.0)),
+                    ((1.0, -1.0, 1.0), (1.0, 1.0, 1.0)),
+                    ((-1.0, -1.0, 1.0), (-1.0, 1.0, 1.0)),
+                ] {
+                    verts.push(corner(a.0, a.1, a.2));
+                    verts.push(corner(b.0, b.1, b.2));
+                }
+            }
+            Self::Sphere(radius) => {
+                let radius = radius as f32 * 0.01;
+                arc(&mut verts, Vec3::ZERO, radius, Vec3::X, Vec3::Z, 0.0, TAU);
+                arc(&mut verts, Vec3::ZERO, radius, Vec3::X, Vec3::Y, 0.0, TAU);
+                arc(&mut verts, Vec3::ZERO, radius, Vec3::Z, Vec3::Y, 0.0, TAU);
+            }
+            Self::Capsule(radius, half) => {
+                let radius = radius as f32 * 0.01;
+                // the half height includes the hemispheres
+                let top = Vec3::Y * (half as f32 * 0.01 - radius).max(0.0);
+                for centre in [top, -top] {
+                    arc(&mut verts, centre, radius, Vec3::X, Vec3::Z, 0.0, TAU);
+                }
+                for axis in [Vec3::X, Vec3::Z] {
+                    arc(&mut verts, top, radius, axis, Vec3::Y, 0.0, PI);
+                    arc(&mut verts, -top, radius, axis, Vec3::Y, PI, TAU);
+                    for side in [axis, -axis] {
+                        verts.push(top + side * radius);
+                        verts.push(-top + side * radius);
+                    }
+                }
+            }
+        }
+        Mesh::new(
+            bevy::render::render_resource::PrimitiveTopology::LineList,
+            default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, verts)
+    }
+
+    fn fill(&self) -> Mesh {
+        match *self {
+            Self::Box(..) | Self::Brush(..) => Cuboid::from_size(self.bounds().1).into(),
+            Self::Sphere(radius) => Sphere::new(radius as f32 * 0.01).into(),
+            Self::Capsule(radius, half) => Capsule3d::new(
+                radius as f32 * 0.01,
+                ((half - radius) as f32 * 0.02).max(0.0),
+            )
+            .into(),
+        }
+    }
+}
+
+/// pushes line segments along an arc between two angles
+fn arc(verts: &mut Vec<Vec3>, centre: Vec3, radius: f32, x: Vec3, y: Vec3, from: f32, to: f32) {
+    const SEGMENTS: usize = 32;
+    let point = |i: usize| {
+        let angle = from + (to - from) * i as f32 / SEGMENTS as f32;
+        centre + (x * angle.cos() + y * angle.sin()) * radius
+    };
+    for i in 0..SEGMENTS {
+        verts.push(point(i));
+        verts.push(point(i + 1));
+    }
+}
+
+/// gets the bounds of the brush from its collision since the model is binary
+fn brush(asset: &Asset, props: &[Property]) -> Option<Shape> {
+    let setup = find(props, "BrushBodySetup")
+        .and_then(|prop| cast!(Property, ObjectProperty, prop))
+        .and_then(|obj| asset.get_export(obj.value))
+        .and_then(|ex| ex.get_normal_export())?;
+    let elems = find(&setup.properties, "AggGeom")
+        .and_then(|prop| cast!(Property, StructProperty, prop))
+        .and_then(|geom| find(&geom.value, "ConvexElems"))
+        .and_then(|prop| cast!(Property, ArrayProperty, prop))?;
+    let mut points = elems
+        .value
+        .iter()
+        .filter_map(|prop| cast!(Property, StructProperty, prop))
+        .filter_map(|elem| find(&elem.value, "VertexData"))
+        .filter_map(|prop| cast!(Property, ArrayProperty, prop))
+        .flat_map(|verts| verts.value.iter().filter_map(as_vector));
+    let first = points.next()?;
+    let (min, max) = points.fold((first, first), |(min, max), point| {
+        (min.min(point), max.max(point))
+    });
+    Some(Shape::Brush(min, max))
+}
+
+fn colour(class: &str) -> LinearRgba {
+    match class {
+        class if class.contains("Trigger") => LinearRgba::rgb(1.0, 0.5, 0.0),
+        class if class.contains("Blocking") => LinearRgba::rgb(1.0, 0.0, 0.5),
+        class if class.contains("PostProcess") => LinearRgba::rgb(0.3, 0.5, 1.0),
+        class if class.contains("KillZ") || class.contains("Pain") => {
+            LinearRgba::rgb(1.0, 0.0, 0.0)
+        }
+        _ => LinearRgba::rgb(0.0, 0.8, 1.0),
+    }
+}
+
+/// the component of the scalar that changed the most
+fn dominant(scalar: DVec3) -> f64 {
+    [scalar.x, scalar.y, scalar.z]
+        .into_iter()
+        .max_by(|a, b| (a - 1.0).abs().total_cmp(&(b - 1.0).abs()))
+        .unwrap_or(1.0)
+}
+
+/// scales a shape's extents instead of its transform, returning false if it can't be
+pub fn resize(asset: &mut Asset, export: usize, scalar: Vec3) -> bool {
+    let Some(shape) = Shape::read(asset, export) else {
+        return false;
+    };
+    let mut names = asset.get_name_map();
+    let Some(norm) = asset.asset_data.exports[export].get_normal_export_mut() else {
+        return false;
+    };
+    let props = &mut norm.properties;
+    // back into unreal's axes
+    let scalar = bevy::math::dvec3(scalar.x as f64, scalar.z as f64, scalar.y as f64);
+    match shape {
+        Shape::Box(extent) => set_vector(props, "BoxExtent", extent * scalar, &mut names),
+        Shape::Sphere(radius) => set_float(
+            props,
+            "SphereRadius",
+            (radius * dominant(scalar)) as f32,
+            &mut names,
+        ),
+        Shape::Capsule(radius, half) => {
+            let radius = radius * dominant(bevy::math::dvec3(scalar.x, scalar.y, 1.0));
+            set_float(props, "CapsuleRadius", radius as f32, &mut names);
+            set_float(
+                props,
+                "CapsuleHalfHeight",
+                (half * scalar.z).max(radius) as f32,
+                &mut names,
+            );
+        }
+        // brushes are scaled by their actor in the engine anyway
+        Shape::Brush(..) => return false,
+    }
+    true
+}
+
+/// draws the real extents of newly spawned volumes and collision components
+pub fn spawn(
+    mut commands: Commands,
+    map: NonSend<Map>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut tints: ResMut<Assets<tint::Tint>>,
+    added: Query<(Entity, &actor::Actor, Option<&Children>), Added<actor::Actor>>,
+    mut cubes: Query<&mut Handle<Mesh>, With<Handle<wire::Wire>>>,
+    mut bounds: Query<&mut Transform, With<bevy_mod_raycast::deferred::RaycastMesh<()>>>,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    for (entity, actor, children) in added.iter() {
+        let mut components = actor.components(map);
+        if !components.contains(&actor.root()) {
+            components.push(actor.root())
+        }
+        for export in components {
+            let Some(shape) = Shape::read(map, export) else {
+                continue;
+            };
+            let colour = colour(&actor.class);
+            let fill = (
+                Fill,
+                MaterialMeshBundle {
+                    mesh: meshes.add(shape.fill()),
+                    material: tints.add(tint::Tint {
+                        colour: colour.with_alpha(0.1),
+                    }),
+                    transform: Transform::from_translation(shape.bounds().0),
+                    ..default()
+                },
+                bevy::pbr::wireframe::NoWireframe,
+            );
+            match cubes.get_mut(entity) {
+                // replace the unit cube so the volume can still be selected
+                Ok(mut mesh) if export == actor.root() => {
+                    *mesh = meshes.add(shape.lines());
+                    let (centre, size) = shape.bounds();
+                    for child in children.into_iter().flatten() {
+                        if let Ok(mut transform) = bounds.get_mut(*child) {
+                            *transform = Transform::from_translation(centre).with_scale(size);
+                        }
+                    }
+                    commands
+                        .entity(entity)
+                        .insert(Volume(export))
+                        .with_children(|parent| {
+                            parent.spawn(fill);
+                        });
+                }
+                _ => {
+                    let transform = match export == actor.root() {
+                        true => Transform::IDENTITY,
+                        false => actor::transform_of(map, export),
+                    };
+                    let lines = (
+                        MaterialMeshBundle {
+                            mesh: meshes.add(shape.lines()),
+                            material: tints.add(tint::Tint {
+                                colour: colour.with_alpha(0.8),
+                            }),
+                            transform,
+                            ..default()
+                        },
+                        bevy::pbr::wireframe::NoWireframe,
+                    );
+                    commands.entity(entity).with_children(|parent| {
+                        parent.spawn(lines).with_children(|parent| {
+                            parent.spawn(fill);
+                        });
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// rebuilds a volume's meshes after it's resized
+pub fn refresh(
+    trigger: Trigger<triggers::RefreshVolume>,
+    map: NonSend<Map>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    volumes: Query<(&Volume, &Handle<Mesh>, &Children)>,
+    mut fills: Query<(&Handle<Mesh>, &mut Transform), With<Fill>>,
+    mut bounds: Query<
+        &mut Transform,
+        (
+            With<bevy_mod_raycast::deferred::RaycastMesh<()>>,
+            Without<Fill>,
+        ),
+    >,
+) {
+    let Some((map, ..)) = &map.0 else { return };
+    let Ok((volume, lines, children)) = volumes.get(trigger.event().0) else {
+        return;
+    };
+    let Some(shape) = Shape::read(map, volume.0) else {
+        return;
+    };
+    if let Some(mesh) = meshes.get_mut(lines) {
+        *mesh = shape.lines();
+    }
+    let (centre, size) = shape.bounds();
+    for child in children.iter() {
+        if let Ok((fill, mut transform)) = fills.get_mut(*child) {
+            if let Some(mesh) = meshes.get_mut(fill) {
+                *mesh = shape.fill();
+            }
+            transform.translation = centre;
+        }
+        if let Ok(mut transform) = bounds.get_mut(*child) {
+            *transform = Transform::from_translation(centre).with_scale(size);
+        }
+    }
+}